@@ -0,0 +1,681 @@
+//! 钱包/池子审计：统计某地址历史交易中的三明治/抢跑攻击
+//!
+//! 通过`getSignaturesForAddress`分页获取该地址的交易，对其中执行成功的DEX交易运行与单笔分析相同的检测。
+//! 钱包审计只分析由该地址签名的交易，按代币和月份汇总其遭受的损失；
+//! 池子审计把池子中的每笔交易都作为候选受害交易，列出打到该池子的所有三明治攻击及其受害者、攻击者和被提取的价值。
+
+use crate::client::{ClientError, SolanaClient, Transaction, MAX_SIGNATURES_PER_PAGE};
+use crate::mev::{fee_payer, MevDetector, UserLoss};
+use crate::mev::token_info::WSOL;
+use crate::scan::AttackerSummary;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 每批获取的交易数（用于输出进度）
+const FETCH_CHUNK: usize = 100;
+
+/// 攻击类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttackKind {
+    Sandwich,
+    Frontrun,
+}
+
+/// 审计中发现的一次攻击
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedAttack {
+    pub kind: AttackKind,
+    /// 受害交易（被审计地址的交易）
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub front_tx: String,
+    /// 三明治攻击的后置交易，抢跑攻击没有
+    pub back_tx: Option<String>,
+    /// 三明治攻击的损失估算，抢跑攻击不计算
+    pub loss: Option<UserLoss>,
+}
+
+/// 单个代币的累计损失
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenLossSummary {
+    pub token_address: String,
+    pub token_symbol: String,
+    /// 在该代币上有损失的攻击次数
+    pub attacks: usize,
+    pub loss_amount: u64,
+    pub loss_amount_ui: f64,
+}
+
+/// 单月汇总
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MonthSummary {
+    pub sandwiches: usize,
+    pub frontruns: usize,
+    /// 以SOL计的损失（其他代币的损失见`WalletAudit::per_token`）
+    pub loss_lamports: u64,
+}
+
+/// 钱包审计报告
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletAudit {
+    pub address: String,
+    /// 获取到的交易签名数（含失败交易）
+    pub signatures_scanned: usize,
+    /// 分析的DEX交易数
+    pub swaps_analyzed: usize,
+    /// 无法获取附近交易而未能分析的DEX交易数
+    pub swaps_unavailable: usize,
+    pub attacks: Vec<AuditedAttack>,
+    /// 按代币汇总的损失（按攻击次数排序）
+    pub per_token: Vec<TokenLossSummary>,
+    /// 按月份（UTC，`YYYY-MM`）汇总，没有区块时间的交易计入`unknown`
+    pub per_month: BTreeMap<String, MonthSummary>,
+    /// 以SOL计的总损失（其他代币的损失见`per_token`）
+    pub total_loss_lamports: u64,
+}
+
+/// 池子审计中三明治攻击的一笔受害交易
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolVictim {
+    pub signature: String,
+    /// 受害交易的费用支付者
+    pub signer: String,
+    pub loss: Option<UserLoss>,
+}
+
+/// 打到池子上的一次三明治攻击（同一对前置/后置交易可能夹住多笔受害交易）
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSandwich {
+    /// 前置交易的费用支付者
+    pub attacker: String,
+    pub front_tx: String,
+    pub back_tx: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub victims: Vec<PoolVictim>,
+    /// 所有受害交易以SOL计的估算损失之和（无法计算损失的受害交易不计入，其他代币的损失见`victims`）
+    pub extracted_lamports: u64,
+}
+
+/// 单个受害地址的汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct VictimSummary {
+    pub address: String,
+    /// 被夹的交易数
+    pub transactions: usize,
+    /// 以SOL计的损失
+    pub loss_lamports: u64,
+}
+
+/// 池子审计报告
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolAudit {
+    pub pool: String,
+    /// 获取到的交易签名数（含失败交易）
+    pub signatures_scanned: usize,
+    /// 池子中执行成功的DEX交易数（含攻击者的交易）
+    pub swaps_analyzed: usize,
+    /// 无法获取附近交易而未能分析的DEX交易数
+    pub swaps_unavailable: usize,
+    /// 按时间从新到旧排列
+    pub sandwiches: Vec<PoolSandwich>,
+    pub total_victims: usize,
+    /// 被夹的交易占池子DEX交易的比例
+    pub victim_ratio: f64,
+    /// 最早和最近一次攻击的区块时间
+    pub first_attack_time: Option<i64>,
+    pub last_attack_time: Option<i64>,
+    /// 按月份（UTC，`YYYY-MM`）统计的攻击次数，没有区块时间的计入`unknown`
+    pub sandwiches_per_month: BTreeMap<String, usize>,
+    /// 按攻击次数排序的攻击者
+    pub attackers: Vec<AttackerSummary>,
+    /// 按损失排序的受害地址
+    pub victims: Vec<VictimSummary>,
+    /// 按代币汇总的被提取价值（按攻击次数排序）
+    pub per_token: Vec<TokenLossSummary>,
+    /// 以SOL计的被提取价值（其他代币见`per_token`）
+    pub total_extracted_lamports: u64,
+}
+
+/// 审计一个钱包地址最近的交易
+///
+/// # 参数
+/// - `address`: 钱包地址。
+/// - `max_signatures`: 最多检查的交易签名数（从最新的交易开始）。
+///
+/// # 返回
+/// 审计报告；获取签名列表失败时返回`ClientError`，单笔交易分析失败只计入`swaps_unavailable`。
+pub async fn audit_wallet(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    address: &str,
+    max_signatures: usize,
+) -> Result<WalletAudit, ClientError> {
+    // 步骤1: 分页获取签名
+    let (signatures_scanned, signatures) = successful_signatures(client, address, max_signatures).await?;
+
+    // 步骤2: 分批获取交易，筛选由该地址签名的DEX交易并逐笔检测
+    let mut audit = WalletAudit {
+        address: address.to_string(),
+        signatures_scanned,
+        swaps_analyzed: 0,
+        swaps_unavailable: 0,
+        attacks: Vec::new(),
+        per_token: Vec::new(),
+        per_month: BTreeMap::new(),
+        total_loss_lamports: 0,
+    };
+    for (chunk_index, chunk) in signatures.chunks(FETCH_CHUNK).enumerate() {
+        for (signature, result) in chunk.iter().zip(client.get_transactions(chunk).await) {
+            let tx = match result {
+                Ok(tx) => tx,
+                Err(e) => {
+                    debug!("无法获取交易{}: {}", signature, e);
+                    continue;
+                }
+            };
+            if tx.is_failed() || !tx.is_signer(address) || !detector.is_dex_transaction(&tx) {
+                continue;
+            }
+
+            audit.swaps_analyzed += 1;
+            match detect_attack(client, detector, address, &tx).await {
+                Ok(Some(attack)) => audit.attacks.push(attack),
+                Ok(None) => {}
+                Err(e) => {
+                    debug!("无法分析交易{}: {}", signature, e);
+                    audit.swaps_unavailable += 1;
+                }
+            }
+        }
+        info!("已检查 {} / {} 笔交易，分析 {} 笔DEX交易，发现 {} 次攻击",
+              ((chunk_index + 1) * FETCH_CHUNK).min(signatures.len()), signatures.len(),
+              audit.swaps_analyzed, audit.attacks.len());
+    }
+    if audit.swaps_unavailable > 0 {
+        warn!("{} 笔DEX交易无法获取所在区块，未计入审计结果", audit.swaps_unavailable);
+    }
+
+    // 步骤3: 汇总
+    audit.per_token = summarize_tokens(audit.attacks.iter().filter_map(|attack| attack.loss.as_ref()));
+    for attack in &audit.attacks {
+        let month = attack.block_time.map_or_else(|| "unknown".to_string(), utc_month);
+        let summary = audit.per_month.entry(month).or_default();
+        match attack.kind {
+            AttackKind::Sandwich => summary.sandwiches += 1,
+            AttackKind::Frontrun => summary.frontruns += 1,
+        }
+        let loss = attack.loss.as_ref().map_or(0, UserLoss::sol_loss_lamports);
+        summary.loss_lamports += loss;
+        audit.total_loss_lamports += loss;
+    }
+
+    Ok(audit)
+}
+
+/// 审计一个池子（或市场）地址最近的交易，找出打到该池子上的三明治攻击
+///
+/// 池子中每笔执行成功的DEX交易都作为候选受害交易；已被认定为攻击交易或受害交易的不再重复检测。
+/// 前置/后置交易与受害交易的账户交集中必须包含池子地址，只经过该池子的其他三明治攻击不计入。
+///
+/// # 参数
+/// - `pool`: 池子或市场账户地址。
+/// - `max_signatures`: 最多检查的交易签名数（从最新的交易开始）。
+///
+/// # 返回
+/// 审计报告；获取签名列表失败时返回`ClientError`，单笔交易分析失败只计入`swaps_unavailable`。
+pub async fn audit_pool(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    pool: &str,
+    max_signatures: usize,
+) -> Result<PoolAudit, ClientError> {
+    // 步骤1: 分页获取签名
+    let (signatures_scanned, signatures) = successful_signatures(client, pool, max_signatures).await?;
+
+    // 步骤2: 分批获取交易并逐笔检测，同一对前置/后置交易的受害交易合并为一次攻击
+    let mut sandwiches: Vec<PoolSandwich> = Vec::new();
+    let mut known: HashSet<String> = HashSet::new();
+    let mut swaps_analyzed = 0;
+    let mut swaps_unavailable = 0;
+    for (chunk_index, chunk) in signatures.chunks(FETCH_CHUNK).enumerate() {
+        for (signature, result) in chunk.iter().zip(client.get_transactions(chunk).await) {
+            let tx = match result {
+                Ok(tx) => tx,
+                Err(e) => {
+                    debug!("无法获取交易{}: {}", signature, e);
+                    continue;
+                }
+            };
+            if tx.is_failed() || !detector.is_dex_transaction(&tx) {
+                continue;
+            }
+
+            swaps_analyzed += 1;
+            if known.contains(signature) {
+                continue;
+            }
+            let (attacker, front_tx, back_tx) = match detect_pool_sandwich(client, detector, pool, &tx).await {
+                Ok(Some(legs)) => legs,
+                Ok(None) => continue,
+                Err(e) => {
+                    debug!("无法分析交易{}: {}", signature, e);
+                    swaps_unavailable += 1;
+                    continue;
+                }
+            };
+
+            let loss = match detector
+                .calculate_sandwich_loss(client, &front_tx, &tx.signature, &back_tx)
+                .await
+            {
+                Ok(loss) => loss,
+                Err(e) => {
+                    debug!("无法计算交易{}的损失: {}", tx.signature, e);
+                    None
+                }
+            };
+            let extracted = loss.as_ref().map_or(0, UserLoss::sol_loss_lamports);
+            let victim = PoolVictim {
+                signature: tx.signature.clone(),
+                signer: fee_payer(&tx).to_string(),
+                loss,
+            };
+
+            known.insert(tx.signature.clone());
+            match sandwiches
+                .iter_mut()
+                .find(|sandwich| sandwich.front_tx == front_tx && sandwich.back_tx == back_tx)
+            {
+                Some(sandwich) => {
+                    sandwich.victims.push(victim);
+                    sandwich.extracted_lamports += extracted;
+                }
+                None => {
+                    known.insert(front_tx.clone());
+                    known.insert(back_tx.clone());
+                    sandwiches.push(PoolSandwich {
+                        attacker,
+                        front_tx,
+                        back_tx,
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        victims: vec![victim],
+                        extracted_lamports: extracted,
+                    });
+                }
+            }
+        }
+        info!("已检查 {} / {} 笔交易，分析 {} 笔DEX交易，发现 {} 次三明治攻击",
+              ((chunk_index + 1) * FETCH_CHUNK).min(signatures.len()), signatures.len(),
+              swaps_analyzed, sandwiches.len());
+    }
+    if swaps_unavailable > 0 {
+        warn!("{} 笔DEX交易无法获取所在区块，未计入审计结果", swaps_unavailable);
+    }
+
+    // 步骤3: 汇总
+    Ok(summarize_pool(pool, signatures_scanned, swaps_analyzed, swaps_unavailable, sandwiches))
+}
+
+/// 检测池子中的一笔交易是否被夹，返回(攻击者, 前置交易, 后置交易)
+///
+/// 要求池子地址出现在前置/后置交易与该交易的账户交集中；攻击交易与受害交易由同一地址签名时不视为攻击。
+async fn detect_pool_sandwich(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    pool: &str,
+    tx: &Transaction,
+) -> Result<Option<(String, String, String)>, ClientError> {
+    let (nearby_transactions, _) = client
+        .get_nearby_transactions(&tx.signature, detector.config.window_transactions, detector.config.window_slots)
+        .await?;
+    let Some(sandwich) = detector.detect_sandwich_attack(&nearby_transactions, &tx.signature) else {
+        return Ok(None);
+    };
+    if !sandwich.account_intersection.iter().any(|account| account == pool) {
+        debug!("交易{}的三明治攻击未经过池子{}", tx.signature, pool);
+        return Ok(None);
+    }
+
+    let signer = |signature: &str| {
+        nearby_transactions
+            .iter()
+            .find(|nearby| nearby.signature == signature)
+            .map_or("", fee_payer)
+    };
+    let attacker = signer(&sandwich.front_tx);
+    let victim_signer = fee_payer(tx);
+    if attacker == victim_signer || signer(&sandwich.back_tx) == victim_signer {
+        return Ok(None);
+    }
+
+    Ok(Some((attacker.to_string(), sandwich.front_tx, sandwich.back_tx)))
+}
+
+/// 汇总池子审计结果
+fn summarize_pool(
+    pool: &str,
+    signatures_scanned: usize,
+    swaps_analyzed: usize,
+    swaps_unavailable: usize,
+    sandwiches: Vec<PoolSandwich>,
+) -> PoolAudit {
+    let mut sandwiches_per_month: BTreeMap<String, usize> = BTreeMap::new();
+    let mut attackers: HashMap<&str, AttackerSummary> = HashMap::new();
+    let mut victims: HashMap<&str, VictimSummary> = HashMap::new();
+    for sandwich in &sandwiches {
+        let month = sandwich.block_time.map_or_else(|| "unknown".to_string(), utc_month);
+        *sandwiches_per_month.entry(month).or_default() += 1;
+
+        let attacker = attackers
+            .entry(&sandwich.attacker)
+            .or_insert_with(|| AttackerSummary {
+                attacker: sandwich.attacker.clone(),
+                sandwiches: 0,
+                victims: 0,
+                victim_loss_lamports: 0,
+            });
+        attacker.sandwiches += 1;
+        attacker.victims += sandwich.victims.len();
+        attacker.victim_loss_lamports += sandwich.extracted_lamports;
+
+        for victim in &sandwich.victims {
+            let summary = victims
+                .entry(&victim.signer)
+                .or_insert_with(|| VictimSummary {
+                    address: victim.signer.clone(),
+                    transactions: 0,
+                    loss_lamports: 0,
+                });
+            summary.transactions += 1;
+            summary.loss_lamports += victim.loss.as_ref().map_or(0, UserLoss::sol_loss_lamports);
+        }
+    }
+
+    let mut attackers: Vec<AttackerSummary> = attackers.into_values().collect();
+    attackers.sort_by(|a, b| {
+        b.sandwiches
+            .cmp(&a.sandwiches)
+            .then(b.victim_loss_lamports.cmp(&a.victim_loss_lamports))
+            .then_with(|| a.attacker.cmp(&b.attacker))
+    });
+    let mut victims: Vec<VictimSummary> = victims.into_values().collect();
+    victims.sort_by(|a, b| {
+        b.loss_lamports
+            .cmp(&a.loss_lamports)
+            .then(b.transactions.cmp(&a.transactions))
+            .then_with(|| a.address.cmp(&b.address))
+    });
+
+    let total_victims = sandwiches.iter().map(|sandwich| sandwich.victims.len()).sum();
+    let block_times = sandwiches.iter().filter_map(|sandwich| sandwich.block_time);
+    PoolAudit {
+        pool: pool.to_string(),
+        signatures_scanned,
+        swaps_analyzed,
+        swaps_unavailable,
+        total_victims,
+        victim_ratio: if swaps_analyzed > 0 { total_victims as f64 / swaps_analyzed as f64 } else { 0.0 },
+        first_attack_time: block_times.clone().min(),
+        last_attack_time: block_times.max(),
+        sandwiches_per_month,
+        attackers,
+        victims,
+        per_token: summarize_tokens(
+            sandwiches
+                .iter()
+                .flat_map(|sandwich| &sandwich.victims)
+                .filter_map(|victim| victim.loss.as_ref()),
+        ),
+        total_extracted_lamports: sandwiches.iter().map(|sandwich| sandwich.extracted_lamports).sum(),
+        sandwiches,
+    }
+}
+
+/// 分页获取地址最近的交易签名，返回(获取到的签名数, 执行成功的交易签名)
+///
+/// 执行失败的交易没有实际成交，直接跳过。
+async fn successful_signatures(
+    client: &SolanaClient,
+    address: &str,
+    max_signatures: usize,
+) -> Result<(usize, Vec<String>), ClientError> {
+    let mut signatures = Vec::new();
+    let mut signatures_scanned = 0;
+    let mut before: Option<String> = None;
+    while signatures_scanned < max_signatures {
+        let limit = (max_signatures - signatures_scanned).min(MAX_SIGNATURES_PER_PAGE);
+        let page = client
+            .get_signatures_for_address(address, before.as_deref(), limit)
+            .await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(last.signature.clone());
+        let oldest_slot = last.slot;
+        signatures_scanned += page.len();
+        signatures.extend(page.into_iter().filter(|info| info.err.is_none()).map(|info| info.signature));
+        debug!("已获取 {} 个签名，最早的位于slot {}", signatures_scanned, oldest_slot);
+    }
+    info!("地址{}共有 {} 笔交易，其中 {} 笔执行成功", address, signatures_scanned, signatures.len());
+    Ok((signatures_scanned, signatures))
+}
+
+/// 对被审计地址的一笔交易运行三明治/抢跑检测
+///
+/// 攻击交易由被审计地址自己签名时（例如连续的两笔swap）不视为攻击。
+async fn detect_attack(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    address: &str,
+    tx: &Transaction,
+) -> Result<Option<AuditedAttack>, ClientError> {
+    let (nearby_transactions, _) = client
+        .get_nearby_transactions(&tx.signature, detector.config.window_transactions, detector.config.window_slots)
+        .await?;
+    let signed_by_wallet = |signature: &str| {
+        nearby_transactions
+            .iter()
+            .any(|nearby| nearby.signature == signature && nearby.is_signer(address))
+    };
+
+    if let Some(sandwich) = detector.detect_sandwich_attack(&nearby_transactions, &tx.signature) {
+        if signed_by_wallet(&sandwich.front_tx) || signed_by_wallet(&sandwich.back_tx) {
+            return Ok(None);
+        }
+        let loss = match detector
+            .calculate_sandwich_loss(client, &sandwich.front_tx, &tx.signature, &sandwich.back_tx)
+            .await
+        {
+            Ok(loss) => loss,
+            Err(e) => {
+                debug!("无法计算交易{}的损失: {}", tx.signature, e);
+                None
+            }
+        };
+        return Ok(Some(AuditedAttack {
+            kind: AttackKind::Sandwich,
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            front_tx: sandwich.front_tx,
+            back_tx: Some(sandwich.back_tx),
+            loss,
+        }));
+    }
+
+    if let Some(frontrun) = detector.detect_frontrun_attack(&nearby_transactions, &tx.signature) {
+        if signed_by_wallet(&frontrun.front_tx) {
+            return Ok(None);
+        }
+        return Ok(Some(AuditedAttack {
+            kind: AttackKind::Frontrun,
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            front_tx: frontrun.front_tx,
+            back_tx: None,
+            loss: None,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// 按代币汇总损失；没有代币明细的损失计为SOL
+fn summarize_tokens<'a>(losses: impl Iterator<Item = &'a UserLoss>) -> Vec<TokenLossSummary> {
+    let mut tokens: BTreeMap<String, TokenLossSummary> = BTreeMap::new();
+    for loss in losses {
+        if loss.token_losses.is_empty() {
+            if loss.estimated_loss_lamports == 0 {
+                continue;
+            }
+            let summary = tokens.entry(WSOL.to_string()).or_insert_with(|| TokenLossSummary {
+                token_address: WSOL.to_string(),
+                token_symbol: "SOL".to_string(),
+                attacks: 0,
+                loss_amount: 0,
+                loss_amount_ui: 0.0,
+            });
+            summary.attacks += 1;
+            summary.loss_amount += loss.estimated_loss_lamports;
+            summary.loss_amount_ui += loss.estimated_loss_lamports as f64 / 1_000_000_000.0;
+            continue;
+        }
+
+        for token_loss in &loss.token_losses {
+            let summary = tokens
+                .entry(token_loss.token_address.clone())
+                .or_insert_with(|| TokenLossSummary {
+                    token_address: token_loss.token_address.clone(),
+                    token_symbol: token_loss.token_symbol.clone(),
+                    attacks: 0,
+                    loss_amount: 0,
+                    loss_amount_ui: 0.0,
+                });
+            summary.attacks += 1;
+            summary.loss_amount += token_loss.loss_amount;
+            summary.loss_amount_ui += token_loss.loss_amount_ui;
+        }
+    }
+
+    let mut per_token: Vec<TokenLossSummary> = tokens.into_values().collect();
+    per_token.sort_by(|a, b| b.attacks.cmp(&a.attacks).then_with(|| a.token_symbol.cmp(&b.token_symbol)));
+    per_token
+}
+
+/// Unix时间戳所在的UTC月份（`YYYY-MM`）
+fn utc_month(timestamp: i64) -> String {
+    // 公历日期换算（Howard Hinnant的civil_from_days算法）
+    let days = timestamp.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}", year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::tests::user_loss;
+    use crate::mev::token_info::USDC;
+
+    fn pool_sandwich(attacker: &str, victims: Vec<(&str, Option<UserLoss>)>) -> PoolSandwich {
+        let victims: Vec<PoolVictim> = victims
+            .into_iter()
+            .map(|(signer, loss)| PoolVictim {
+                signature: format!("{}-tx", signer),
+                signer: signer.to_string(),
+                loss,
+            })
+            .collect();
+        PoolSandwich {
+            attacker: attacker.to_string(),
+            front_tx: format!("{}-front", attacker),
+            back_tx: format!("{}-back", attacker),
+            slot: 1,
+            block_time: Some(0),
+            extracted_lamports: victims
+                .iter()
+                .filter_map(|victim| victim.loss.as_ref())
+                .map(UserLoss::sol_loss_lamports)
+                .sum(),
+            victims,
+        }
+    }
+
+    #[test]
+    fn pool_summary_keeps_token_losses_out_of_sol_totals() {
+        let sandwiches = vec![
+            pool_sandwich("bot", vec![
+                ("alice", Some(user_loss(&[(WSOL, 5_000)]))),
+                ("bob", Some(user_loss(&[(USDC, 2_000_000)]))),
+            ]),
+            pool_sandwich("bot", vec![("alice", None)]),
+        ];
+        let audit = summarize_pool("pool", 10, 6, 0, sandwiches);
+
+        assert_eq!(audit.sandwiches[0].extracted_lamports, 5_000);
+        assert_eq!(audit.total_extracted_lamports, 5_000);
+        assert_eq!(audit.total_victims, 3);
+        assert_eq!(audit.attackers.len(), 1);
+        assert_eq!(
+            (audit.attackers[0].sandwiches, audit.attackers[0].victims, audit.attackers[0].victim_loss_lamports),
+            (2, 3, 5_000)
+        );
+        let victims: Vec<(&str, usize, u64)> = audit
+            .victims
+            .iter()
+            .map(|victim| (victim.address.as_str(), victim.transactions, victim.loss_lamports))
+            .collect();
+        assert_eq!(victims, vec![("alice", 2, 5_000), ("bob", 1, 0)]);
+
+        let per_token: Vec<(&str, u64)> = audit
+            .per_token
+            .iter()
+            .map(|token| (token.token_address.as_str(), token.loss_amount))
+            .collect();
+        assert_eq!(per_token, vec![(USDC, 2_000_000), (WSOL, 5_000)]);
+    }
+
+    #[test]
+    fn utc_month_at_epoch() {
+        assert_eq!(utc_month(0), "1970-01");
+        assert_eq!(utc_month(2_678_399), "1970-01");
+        assert_eq!(utc_month(2_678_400), "1970-02");
+    }
+
+    #[test]
+    fn utc_month_around_leap_days() {
+        // 2024-02-29 00:00:00 / 23:59:59，2024-03-01 00:00:00
+        assert_eq!(utc_month(1_709_164_800), "2024-02");
+        assert_eq!(utc_month(1_709_251_199), "2024-02");
+        assert_eq!(utc_month(1_709_251_200), "2024-03");
+        // 2000是闰年，1900不是
+        assert_eq!(utc_month(951_825_600), "2000-02");
+        assert_eq!(utc_month(-2_203_891_201), "1900-02");
+        assert_eq!(utc_month(-2_203_891_200), "1900-03");
+    }
+
+    #[test]
+    fn utc_month_across_year_boundary() {
+        // 2023-12-31 23:59:59 / 2024-01-01 00:00:00
+        assert_eq!(utc_month(1_704_067_199), "2023-12");
+        assert_eq!(utc_month(1_704_067_200), "2024-01");
+    }
+
+    #[test]
+    fn utc_month_before_1970() {
+        assert_eq!(utc_month(-1), "1969-12");
+        assert_eq!(utc_month(-315_619_200), "1960-01");
+    }
+}
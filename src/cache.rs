@@ -0,0 +1,333 @@
+//! RPC结果的内存缓存和磁盘缓存
+//!
+//! 交易以`getTransaction`结果的JSON格式缓存（包含`meta`），内存缓存中的区块只记录其中的交易签名，
+//! 因此通过`getBlock`获取的交易也能直接命中按签名的查询。
+//! 磁盘缓存按slot/签名各保存一个gzip压缩的JSON文件，用于离线重复分析。
+//! 内存缓存只在一个客户端（单一承诺级别）内共享；磁盘缓存由客户端按承诺级别分目录打开。
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{debug, warn};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Transaction(String),
+    Block(u64),
+}
+
+#[derive(Debug, Clone)]
+enum CacheEntry {
+    Transaction(Value),
+    Block(Vec<String>),
+}
+
+/// 按估算内存占用限制容量的LRU缓存
+#[derive(Debug)]
+struct LruCache {
+    capacity_bytes: usize,
+    used_bytes: usize,
+    /// 键 -> (条目, 估算大小, 最近使用序号)
+    entries: HashMap<CacheKey, (CacheEntry, usize, u64)>,
+    /// 最近使用序号 -> 键，序号最小的最久未使用
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+impl LruCache {
+    fn new(capacity_bytes: usize) -> Self {
+        Self {
+            capacity_bytes,
+            used_bytes: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<CacheEntry> {
+        self.tick += 1;
+        let (entry, _, last_used) = self.entries.get_mut(key)?;
+        self.recency.remove(last_used);
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key.clone());
+        Some(entry.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, entry: CacheEntry) {
+        let size = entry_size(&key, &entry);
+        // 单个条目超过总容量时不缓存
+        if size > self.capacity_bytes {
+            return;
+        }
+
+        self.remove(&key);
+        while self.used_bytes + size > self.capacity_bytes {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((_, oldest_size, _)) = self.entries.remove(&oldest) {
+                self.used_bytes -= oldest_size;
+            }
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, (entry, size, self.tick));
+        self.used_bytes += size;
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some((_, size, last_used)) = self.entries.remove(key) {
+            self.recency.remove(&last_used);
+            self.used_bytes -= size;
+        }
+    }
+}
+
+/// 估算条目占用的内存（字节）
+fn entry_size(key: &CacheKey, entry: &CacheEntry) -> usize {
+    let key_size = match key {
+        CacheKey::Transaction(signature) => signature.len(),
+        CacheKey::Block(_) => 0,
+    };
+    let entry_size = match entry {
+        CacheEntry::Transaction(value) => value_size(value),
+        CacheEntry::Block(signatures) => signatures.iter().map(|s| s.len() + 24).sum(),
+    };
+    std::mem::size_of::<(CacheKey, CacheEntry, usize, u64)>() + key_size + entry_size
+}
+
+/// 估算JSON值占用的内存（字节）
+fn value_size(value: &Value) -> usize {
+    let node = std::mem::size_of::<Value>();
+    node + match value {
+        Value::String(s) => s.len(),
+        Value::Array(items) => items.iter().map(value_size).sum(),
+        Value::Object(map) => map.iter().map(|(k, v)| k.len() + node + value_size(v)).sum(),
+        _ => 0,
+    }
+}
+
+/// 交易和区块的共享内存缓存（线程安全）
+#[derive(Debug)]
+pub struct MemoryCache {
+    inner: Mutex<LruCache>,
+}
+
+impl MemoryCache {
+    /// 创建缓存，`capacity_bytes`为0时不缓存任何内容
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(LruCache::new(capacity_bytes)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 按签名查询`getTransaction`格式的交易
+    pub fn get_transaction(&self, signature: &str) -> Option<Value> {
+        match self.lock().get(&CacheKey::Transaction(signature.to_string()))? {
+            CacheEntry::Transaction(value) => Some(value),
+            CacheEntry::Block(_) => None,
+        }
+    }
+
+    pub fn put_transaction(&self, signature: &str, value: Value) {
+        self.lock().insert(
+            CacheKey::Transaction(signature.to_string()),
+            CacheEntry::Transaction(value),
+        );
+    }
+
+    /// 按slot查询区块中的所有交易（按区块内顺序），任一交易已被淘汰时视为未命中
+    pub fn get_block(&self, slot: u64) -> Option<Vec<Value>> {
+        let mut cache = self.lock();
+        let CacheEntry::Block(signatures) = cache.get(&CacheKey::Block(slot))? else {
+            return None;
+        };
+        signatures
+            .iter()
+            .map(|signature| match cache.get(&CacheKey::Transaction(signature.clone()))? {
+                CacheEntry::Transaction(value) => Some(value),
+                CacheEntry::Block(_) => None,
+            })
+            .collect()
+    }
+
+    /// 缓存区块中的所有交易（`(签名, getTransaction格式的交易)`，按区块内顺序）
+    pub fn put_block(&self, slot: u64, transactions: Vec<(String, Value)>) {
+        let mut cache = self.lock();
+        let signatures = transactions.iter().map(|(signature, _)| signature.clone()).collect();
+        for (signature, value) in transactions {
+            cache.insert(CacheKey::Transaction(signature), CacheEntry::Transaction(value));
+        }
+        cache.insert(CacheKey::Block(slot), CacheEntry::Block(signatures));
+    }
+}
+
+/// 磁盘缓存目录中区块和交易的子目录
+const BLOCK_DIR: &str = "blocks";
+const TRANSACTION_DIR: &str = "transactions";
+const FILE_EXTENSION: &str = "json.gz";
+
+/// 超出容量时淘汰到容量的这一比例，避免每次写入都扫描目录
+const EVICT_TARGET_RATIO: f64 = 0.9;
+
+/// 持久化的区块和交易缓存（gzip压缩的JSON文件）
+///
+/// 超出容量上限时按文件修改时间淘汰最旧的文件。
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    limit_bytes: u64,
+    used_bytes: Mutex<u64>,
+}
+
+impl DiskCache {
+    /// 打开（必要时创建）缓存目录
+    pub fn open(dir: impl Into<PathBuf>, limit_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join(BLOCK_DIR))?;
+        fs::create_dir_all(dir.join(TRANSACTION_DIR))?;
+
+        let cache = Self {
+            dir,
+            limit_bytes,
+            used_bytes: Mutex::new(0),
+        };
+        let used: u64 = cache.cached_files().iter().map(|(_, size, _)| size).sum();
+        *cache.used_bytes.lock().unwrap_or_else(|e| e.into_inner()) = used;
+        debug!("磁盘缓存{}已使用{}KB", cache.dir.display(), used / 1024);
+        Ok(cache)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn get_transaction(&self, signature: &str) -> Option<Value> {
+        read_compressed_json(&self.transaction_path(signature)?)
+    }
+
+    pub fn put_transaction(&self, signature: &str, value: &Value) {
+        if let Some(path) = self.transaction_path(signature) {
+            self.write(&path, value);
+        }
+    }
+
+    /// 区块中所有`getTransaction`格式的交易（按区块内顺序）
+    pub fn get_block(&self, slot: u64) -> Option<Vec<Value>> {
+        match read_compressed_json(&self.block_path(slot))? {
+            Value::Array(transactions) => Some(transactions),
+            _ => None,
+        }
+    }
+
+    pub fn put_block(&self, slot: u64, transactions: &[Value]) {
+        self.write(&self.block_path(slot), &Value::Array(transactions.to_vec()));
+    }
+
+    fn block_path(&self, slot: u64) -> PathBuf {
+        self.dir.join(BLOCK_DIR).join(format!("{}.{}", slot, FILE_EXTENSION))
+    }
+
+    /// 签名作为文件名，只接受base58字符
+    fn transaction_path(&self, signature: &str) -> Option<PathBuf> {
+        if signature.is_empty() || !signature.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        Some(self.dir.join(TRANSACTION_DIR).join(format!("{}.{}", signature, FILE_EXTENSION)))
+    }
+
+    /// 写入文件（先写临时文件再重命名，避免中断时留下不完整的缓存），失败只记录警告
+    fn write(&self, path: &Path, value: &Value) {
+        let previous_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let tmp_path = path.with_extension("tmp");
+        let result = write_compressed_json(&tmp_path, value).and_then(|size| {
+            fs::rename(&tmp_path, path)?;
+            Ok(size)
+        });
+
+        match result {
+            Ok(size) => {
+                let mut used = self.used_bytes.lock().unwrap_or_else(|e| e.into_inner());
+                *used = (*used + size).saturating_sub(previous_size);
+                if *used > self.limit_bytes {
+                    *used = self.evict(path);
+                }
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&tmp_path);
+                warn!("写入磁盘缓存{}失败: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// 按修改时间删除最旧的文件直到低于容量，返回剩余占用；刚写入的文件不删除
+    fn evict(&self, keep: &Path) -> u64 {
+        let mut files = self.cached_files();
+        files.sort_by_key(|(_, _, modified)| *modified);
+
+        let target = (self.limit_bytes as f64 * EVICT_TARGET_RATIO) as u64;
+        let mut used: u64 = files.iter().map(|(_, size, _)| size).sum();
+        for (path, size, _) in files {
+            if used <= target {
+                break;
+            }
+            if path == keep {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => used -= size,
+                Err(e) => warn!("删除磁盘缓存{}失败: {}", path.display(), e),
+            }
+        }
+        debug!("磁盘缓存淘汰后已使用{}KB", used / 1024);
+        used
+    }
+
+    /// 缓存目录中的所有文件：(路径, 大小, 修改时间)
+    fn cached_files(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        [BLOCK_DIR, TRANSACTION_DIR]
+            .iter()
+            .filter_map(|sub_dir| fs::read_dir(self.dir.join(sub_dir)).ok())
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok()?;
+                metadata.is_file().then(|| {
+                    (entry.path(), metadata.len(), metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH))
+                })
+            })
+            .collect()
+    }
+}
+
+fn read_compressed_json(path: &Path) -> Option<Value> {
+    let file = File::open(path).ok()?;
+    match serde_json::from_reader(BufReader::new(GzDecoder::new(file))) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("磁盘缓存{}已损坏: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// 写入gzip压缩的JSON，返回文件大小
+fn write_compressed_json(path: &Path, value: &Value) -> io::Result<u64> {
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+    serde_json::to_writer(&mut encoder, value)?;
+    encoder.finish()?.flush()?;
+    Ok(fs::metadata(path)?.len())
+}
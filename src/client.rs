@@ -1,6 +1,14 @@
-use reqwest::Client;
+use crate::cache::{DiskCache, MemoryCache};
+use crate::rate_limit::Backoff;
+use crate::rpc_pool::{RpcEndpoint, RpcPool};
+use crate::settings::{CacheConfig, Commitment, RpcConfig, RpcEndpointConfig};
+use log::{debug, info, warn};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::fmt;
+use std::path::Path;
 use std::time::Duration;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -11,6 +19,15 @@ pub struct Transaction {
     #[serde(rename = "blockTime")]
     pub block_time: Option<i64>,
     pub transaction: TransactionData,
+    /// 交易执行结果和余额变化，`getTransaction`和`getBlock`都会返回
+    #[serde(default)]
+    pub meta: Option<TransactionMeta>,
+    /// 返回该交易的RPC端点名称
+    #[serde(skip)]
+    pub served_by: Option<String>,
+    /// 在区块中的原始位置（从0开始，过滤投票交易前的索引），只有通过`getBlock`获取时才有
+    #[serde(skip)]
+    pub block_index: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -27,6 +44,28 @@ pub struct Message {
     #[serde(rename = "recentBlockhash")]
     pub recent_blockhash: Option<String>,
     pub header: Option<MessageHeader>,
+    #[serde(rename = "addressTableLookups", default)]
+    pub address_table_lookups: Vec<AddressTableLookup>,
+}
+
+/// v0交易中引用的地址查找表
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AddressTableLookup {
+    #[serde(rename = "accountKey")]
+    pub account_key: String,
+    #[serde(rename = "writableIndexes")]
+    pub writable_indexes: Vec<u8>,
+    #[serde(rename = "readonlyIndexes")]
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// 通过地址查找表加载的账户（已由RPC节点解析）
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -45,50 +84,79 @@ pub struct Instruction {
     pub program_id_index: u8,
     pub accounts: Vec<u8>,
     pub data: String,
+    /// 调用栈深度（顶层指令为1，CPI逐层递增），旧版本RPC不返回
+    #[serde(rename = "stackHeight", default)]
+    pub stack_height: Option<u32>,
 }
 
-pub struct SolanaClient {
-    rpc_url: String,
-    client: Client,
+/// 某条顶层指令通过CPI产生的内部指令
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct InnerInstructions {
+    /// 父指令（顶层指令）的索引
+    pub index: u8,
+    pub instructions: Vec<Instruction>,
 }
 
-impl SolanaClient {
-    /// 创建一个新的Solana客户端实例。
-    ///
-    /// # 参数
-    /// - `rpc_url`: Solana RPC节点的URL。
+/// 展开后的指令树节点
+#[derive(Debug, Clone, Copy)]
+pub struct InstructionNode<'a> {
+    /// 所属顶层指令的索引
+    pub top_level_index: usize,
+    /// 在父指令内部指令中的序号，顶层指令本身为`None`
+    pub inner_index: Option<usize>,
+    pub instruction: &'a Instruction,
+}
+
+impl InstructionNode<'_> {
+    /// 是否为CPI产生的内部指令
+    pub fn is_inner(&self) -> bool {
+        self.inner_index.is_some()
+    }
+}
+
+impl Transaction {
+    /// 获取完整的账户列表
     ///
-    /// # 返回
-    /// `Result`，包含`SolanaClient`实例或`reqwest::Error`。
-    pub fn new(rpc_url: String) -> Result<Self, reqwest::Error> {
-        Ok(Self {
-            rpc_url,
-            client: Client::builder().timeout(Duration::from_secs(30)).build()?,
-        })
+    /// 顺序与指令中的账户索引一致：静态账户 + ALT加载的可写账户 + ALT加载的只读账户。
+    pub fn account_keys(&self) -> Vec<String> {
+        let mut keys = self.transaction.message.account_keys.clone();
+        if let Some(loaded) = self.loaded_addresses() {
+            keys.extend(loaded.writable.iter().cloned());
+            keys.extend(loaded.readonly.iter().cloned());
+        }
+        keys
     }
 
-    /// 判断指定索引的账户是否可写
-    pub fn is_account_writable(&self, account_index: usize, message: &Message) -> bool {
+    /// 判断指定索引的账户是否可写（索引基于`account_keys()`）
+    pub fn is_account_writable(&self, account_index: usize) -> bool {
+        let message = &self.transaction.message;
+        let static_len = message.account_keys.len();
+
+        // ALT加载的账户：可写账户在前，只读账户在后
+        if account_index >= static_len {
+            let loaded_writable = self.loaded_addresses().map_or(0, |loaded| loaded.writable.len());
+            return account_index < static_len + loaded_writable;
+        }
+
         if let Some(header) = &message.header {
             let num_required_signatures = header.num_required_signatures as usize;
             let num_readonly_signed_accounts = header.num_readonly_signed_accounts as usize;
             let num_readonly_unsigned_accounts = header.num_readonly_unsigned_accounts as usize;
 
-            // Solana账户排序：
+            // Solana静态账户排序：
             // 1. 需要签名的可写账户 (0 to num_required_signatures - num_readonly_signed_accounts - 1)
             // 2. 需要签名的只读账户 (num_required_signatures - num_readonly_signed_accounts to num_required_signatures - 1)
-            // 3. 不需要签名的可写账户 (num_required_signatures to account_keys.len() - num_readonly_unsigned_accounts - 1)
-            // 4. 不需要签名的只读账户 (account_keys.len() - num_readonly_unsigned_accounts to account_keys.len() - 1)
+            // 3. 不需要签名的可写账户 (num_required_signatures to static_len - num_readonly_unsigned_accounts - 1)
+            // 4. 不需要签名的只读账户 (static_len - num_readonly_unsigned_accounts to static_len - 1)
 
             if account_index < num_required_signatures {
                 // 需要签名的账户
-                account_index < (num_required_signatures - num_readonly_signed_accounts)
+                account_index < num_required_signatures.saturating_sub(num_readonly_signed_accounts)
             } else {
                 // 不需要签名的账户
-                let unsigned_start = num_required_signatures;
                 let readonly_unsigned_start =
-                    message.account_keys.len() - num_readonly_unsigned_accounts;
-                account_index >= unsigned_start && account_index < readonly_unsigned_start
+                    static_len.saturating_sub(num_readonly_unsigned_accounts);
+                account_index < readonly_unsigned_start
             }
         } else {
             // 如果没有header信息，无法判断，默认认为都可写（保守处理）
@@ -96,131 +164,837 @@ impl SolanaClient {
         }
     }
 
-    /// 获取指定签名的Solana交易详情。
+    /// 按执行顺序展开完整的指令树：每条顶层指令后紧跟其CPI内部指令
+    pub fn instruction_tree(&self) -> Vec<InstructionNode<'_>> {
+        let mut nodes = Vec::new();
+
+        for (top_level_index, instruction) in self.transaction.message.instructions.iter().enumerate() {
+            nodes.push(InstructionNode {
+                top_level_index,
+                inner_index: None,
+                instruction,
+            });
+
+            let inner = self
+                .inner_instructions()
+                .iter()
+                .filter(|group| group.index as usize == top_level_index)
+                .flat_map(|group| group.instructions.iter());
+
+            for (inner_index, instruction) in inner.enumerate() {
+                nodes.push(InstructionNode {
+                    top_level_index,
+                    inner_index: Some(inner_index),
+                    instruction,
+                });
+            }
+        }
+
+        nodes
+    }
+
+    /// 查询代币账户的mint（索引基于`account_keys()`）
+    pub fn token_mint(&self, account_index: usize) -> Option<&str> {
+        self.post_token_balances()
+            .iter()
+            .chain(self.pre_token_balances().iter())
+            .find(|balance| balance.account_index == account_index)
+            .map(|balance| balance.mint.as_str())
+    }
+
+    /// 代币账户在交易前后的数量变化（post - pre，最小单位）
+    ///
+    /// 交易中创建或关闭的账户，缺失一侧按0计算。
+    pub fn token_amount_change(&self, account_index: usize) -> Option<i128> {
+        let amount_of = |balances: &[TokenBalance]| -> Option<i128> {
+            balances
+                .iter()
+                .find(|balance| balance.account_index == account_index)
+                .and_then(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+        };
+
+        let pre = amount_of(self.pre_token_balances());
+        let post = amount_of(self.post_token_balances());
+        if pre.is_none() && post.is_none() {
+            return None;
+        }
+        Some(post.unwrap_or(0) - pre.unwrap_or(0))
+    }
+
+    /// 交易的位置：`(slot, 区块内原始索引)`，不是通过`getBlock`获取时没有索引
+    pub fn position(&self) -> (u64, Option<usize>) {
+        (self.slot, self.block_index)
+    }
+
+    /// 该地址是否为交易的签名者
+    pub fn is_signer(&self, address: &str) -> bool {
+        let signers = self
+            .transaction
+            .message
+            .header
+            .as_ref()
+            .map_or(1, |header| header.num_required_signatures as usize);
+        self.transaction.message.account_keys.iter().take(signers).any(|key| key == address)
+    }
+
+    /// 交易执行失败时的错误（`meta.err`），成功或缺少meta时为`None`
+    pub fn execution_error(&self) -> Option<&Value> {
+        self.meta.as_ref()?.err.as_ref()
+    }
+
+    /// 交易是否执行失败（失败的交易只扣手续费，不产生任何swap效果）
+    pub fn is_failed(&self) -> bool {
+        self.execution_error().is_some()
+    }
+
+    /// v0交易通过地址查找表(ALT)加载的账户
+    pub fn loaded_addresses(&self) -> Option<&LoadedAddresses> {
+        self.meta.as_ref().map(|meta| &meta.loaded_addresses)
+    }
+
+    /// CPI产生的内部指令
+    pub fn inner_instructions(&self) -> &[InnerInstructions] {
+        self.meta.as_ref().map_or(&[], |meta| &meta.inner_instructions)
+    }
+
+    /// 程序日志
+    pub fn log_messages(&self) -> &[String] {
+        self.meta.as_ref().map_or(&[], |meta| &meta.log_messages)
+    }
+
+    /// 交易前的代币余额
+    pub fn pre_token_balances(&self) -> &[TokenBalance] {
+        self.meta.as_ref().map_or(&[], |meta| &meta.pre_token_balances)
+    }
+
+    /// 交易后的代币余额
+    pub fn post_token_balances(&self) -> &[TokenBalance] {
+        self.meta.as_ref().map_or(&[], |meta| &meta.post_token_balances)
+    }
+}
+
+/// Solana JSON-RPC错误码（见solana-rpc-client-api）
+pub mod rpc_error_codes {
+    /// 区块已被清理（节点只保留最近的账本）
+    pub const BLOCK_CLEANED_UP: i64 = -32001;
+    /// 区块暂不可用（通常是刚产出的slot）
+    pub const BLOCK_NOT_AVAILABLE: i64 = -32004;
+    /// 节点不健康（落后于集群）
+    pub const NODE_UNHEALTHY: i64 = -32005;
+    /// slot被跳过，或因节点从快照启动而缺失
+    pub const SLOT_SKIPPED: i64 = -32007;
+    /// slot被跳过，或在长期存储中缺失
+    pub const LONG_TERM_STORAGE_SLOT_SKIPPED: i64 = -32009;
+    /// 节点不提供交易历史
+    pub const TRANSACTION_HISTORY_NOT_AVAILABLE: i64 = -32011;
+    /// 区块状态暂不可用
+    pub const BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
+    /// 节点尚未到达请求的最小上下文slot
+    pub const MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+}
+
+/// `SolanaClient`请求失败的原因
+#[derive(Debug)]
+pub enum ClientError {
+    /// 请求超时
+    Timeout,
+    /// 连接失败等传输层错误
+    Http(reqwest::Error),
+    /// 被RPC节点限流（HTTP 429），`retry_after`来自`Retry-After`响应头
+    RateLimited { retry_after: Option<Duration> },
+    /// 非2xx且不是JSON-RPC格式的HTTP响应
+    HttpStatus { status: u16, body: String },
+    /// JSON-RPC返回的错误对象
+    Rpc { code: i64, message: String },
+    /// 请求成功但结果为空（交易或区块不存在，或超出节点保留的历史）
+    NotFound,
+    /// 目标交易不在其所属slot的区块中
+    NotInBlock { signature: String, slot: u64 },
+    /// 响应无法解析
+    Decode(serde_json::Error),
+    /// 没有可处理该方法的RPC端点
+    NoEndpoint { method: String },
+    /// 离线模式下缓存未命中
+    NotCached { method: String },
+    /// RPC端点配置无效
+    InvalidConfig(String),
+}
+
+impl ClientError {
+    /// JSON-RPC错误码
+    pub fn rpc_code(&self) -> Option<i64> {
+        match self {
+            ClientError::Rpc { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// 是否被限流
+    pub fn is_rate_limited(&self) -> bool {
+        // 部分RPC服务商在JSON-RPC错误中返回429
+        matches!(self, ClientError::RateLimited { .. }) || self.rpc_code() == Some(429)
+    }
+
+    /// 是否为暂时性错误，稍后重试可能成功
+    pub fn is_retryable(&self) -> bool {
+        use rpc_error_codes::*;
+        match self {
+            ClientError::Timeout | ClientError::RateLimited { .. } => true,
+            ClientError::Http(e) => e.is_connect() || e.is_request(),
+            ClientError::HttpStatus { status, .. } => *status >= 500,
+            ClientError::Rpc { code, .. } => matches!(
+                *code,
+                429 | BLOCK_NOT_AVAILABLE
+                    | NODE_UNHEALTHY
+                    | BLOCK_STATUS_NOT_AVAILABLE_YET
+                    | MIN_CONTEXT_SLOT_NOT_REACHED
+            ),
+            _ => false,
+        }
+    }
+
+    /// 节点已裁剪该部分历史，需要换用归档节点
+    ///
+    /// -32007/-32009也可能表示slot确实被跳过，但对目标交易所在的slot而言只可能是历史缺失。
+    pub fn is_history_unavailable(&self) -> bool {
+        use rpc_error_codes::*;
+        matches!(
+            self.rpc_code(),
+            Some(
+                BLOCK_CLEANED_UP
+                    | SLOT_SKIPPED
+                    | LONG_TERM_STORAGE_SLOT_SKIPPED
+                    | TRANSACTION_HISTORY_NOT_AVAILABLE
+            )
+        )
+    }
+
+    /// slot被跳过（该slot没有产出区块）
+    pub fn is_slot_skipped(&self) -> bool {
+        use rpc_error_codes::*;
+        matches!(self.rpc_code(), Some(SLOT_SKIPPED | LONG_TERM_STORAGE_SLOT_SKIPPED))
+    }
+
+    /// 建议的重试等待时间（仅限流响应携带）
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    fn from_rpc_error(error: &Value) -> Self {
+        ClientError::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or_default(),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Timeout => write!(f, "RPC request timed out"),
+            ClientError::Http(e) => write!(f, "RPC request failed: {}", e),
+            ClientError::RateLimited { retry_after: Some(after) } => {
+                write!(f, "rate limited by RPC node, retry after {}s", after.as_secs())
+            }
+            ClientError::RateLimited { retry_after: None } => write!(f, "rate limited by RPC node"),
+            ClientError::HttpStatus { status, body } => write!(f, "HTTP {}: {}", status, body),
+            ClientError::Rpc { code, message } => write!(f, "RPC error {}: {}", code, message),
+            ClientError::NotFound => write!(f, "not found"),
+            ClientError::NotInBlock { signature, slot } => {
+                write!(f, "transaction {} not found in block {}", signature, slot)
+            }
+            ClientError::Decode(e) => write!(f, "failed to decode RPC response: {}", e),
+            ClientError::NoEndpoint { method } => write!(f, "no RPC endpoint configured for {}", method),
+            ClientError::NotCached { method } => {
+                write!(f, "offline mode: {} result is not in the disk cache", method)
+            }
+            ClientError::InvalidConfig(message) => write!(f, "invalid RPC configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Http(e) => Some(e),
+            ClientError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ClientError::Timeout
+        } else {
+            // URL中可能带有API key，不输出到日志
+            ClientError::Http(e.without_url())
+        }
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        ClientError::Decode(e)
+    }
+}
+
+/// 从单个JSON-RPC响应对象中取出`result`（可能为`null`）或错误
+fn rpc_result(json: &Value) -> Result<Value, ClientError> {
+    if let Some(error) = json.get("error") {
+        return Err(ClientError::from_rpc_error(error));
+    }
+    Ok(json.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// 结果来自缓存时`served_by`中的名称
+const CACHE_ENDPOINT: &str = "cache";
+
+/// 一次RPC调用的结果及返回它的端点
+struct RpcResponse {
+    result: Value,
+    endpoint: String,
+}
+
+pub struct SolanaClient {
+    client: Client,
+    pool: RpcPool,
+    backoff: Backoff,
+    max_retries: u32,
+    batch_size: usize,
+    commitment: Commitment,
+    /// 获取区块时丢弃投票/质押交易
+    skip_vote_transactions: bool,
+    cache: MemoryCache,
+    disk_cache: Option<DiskCache>,
+    /// 离线模式：只从缓存读取，不发送任何RPC请求
+    offline: bool,
+}
+
+impl SolanaClient {
+    /// 创建一个新的Solana客户端实例。
     ///
     /// # 参数
-    /// - `signature`: 交易签名（哈希）。
+    /// - `endpoints`: RPC端点列表，按优先级使用并自动故障转移。
+    /// - `config`: 超时、重试、限流和承诺级别配置。
+    /// - `cache`: 交易和区块缓存配置。
     ///
     /// # 返回
-    /// `Result`，包含`Transaction`结构体或错误信息。
-    pub async fn get_transaction(
+    /// `Result`，包含`SolanaClient`实例或`ClientError`。
+    pub fn new(
+        endpoints: &[RpcEndpointConfig],
+        config: &RpcConfig,
+        cache: &CacheConfig,
+    ) -> Result<Self, ClientError> {
+        // 不同承诺级别的结果分目录保存，切换到finalized后不会读到confirmed时缓存的数据
+        let disk_cache = match &cache.disk_dir {
+            Some(dir) => Some(
+                DiskCache::open(
+                    Path::new(dir).join(config.commitment.for_ledger_query().as_str()),
+                    cache.disk_limit_mb * 1024 * 1024,
+                )
+                    .map_err(|e| ClientError::InvalidConfig(format!("无法打开磁盘缓存{}: {}", dir, e)))?,
+            ),
+            None => None,
+        };
+        if let Some(disk_cache) = &disk_cache {
+            info!("使用磁盘缓存: {}", disk_cache.dir().display());
+        }
+        if cache.offline && disk_cache.is_none() {
+            return Err(ClientError::InvalidConfig("离线模式需要配置cache.disk_dir".to_string()));
+        }
+
+        Ok(Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(config.timeout_secs))
+                .build()?,
+            pool: RpcPool::new(endpoints, config).map_err(ClientError::InvalidConfig)?,
+            backoff: Backoff {
+                initial: Duration::from_millis(config.initial_backoff_ms),
+                max: Duration::from_millis(config.max_backoff_ms),
+            },
+            max_retries: config.max_retries,
+            batch_size: config.batch_size,
+            commitment: config.commitment,
+            skip_vote_transactions: config.skip_vote_transactions,
+            cache: MemoryCache::new(cache.memory_limit_mb * 1024 * 1024),
+            disk_cache,
+            offline: cache.offline,
+        })
+    }
+
+    /// 对所有端点调用`getHealth`并更新健康状态
+    ///
+    /// # 返回
+    /// 健康的端点数量
+    pub async fn check_endpoints(&self) -> usize {
+        let mut healthy = 0;
+        for endpoint in self.pool.endpoints() {
+            match self.send_request(endpoint, "getHealth", &serde_json::json!([])).await {
+                Ok(_) => {
+                    endpoint.mark_healthy();
+                    healthy += 1;
+                    info!("RPC端点{}（优先级{}）正常", endpoint.name, endpoint.priority);
+                }
+                Err(e) => {
+                    endpoint.mark_unhealthy(self.pool.unhealthy_cooldown);
+                    warn!("RPC端点{}（优先级{}）不可用: {}", endpoint.name, endpoint.priority, e);
+                }
+            }
+        }
+        healthy
+    }
+
+    /// 发送JSON-RPC请求并返回`result`字段（可能为`null`）及返回它的端点
+    ///
+    /// 按优先级依次尝试支持该方法的端点：暂时性错误（限流、超时、5xx）会把端点标记为不健康，
+    /// 历史缺失或结果为空时转到下一个端点（其他节点可能保留了更早的账本）。
+    /// 所有端点都遇到暂时性错误时按指数退避重试，响应带有`Retry-After`时以其为准（不超过退避上限）。
+    async fn rpc_request(&self, method: &str, params: Value) -> Result<RpcResponse, ClientError> {
+        if self.offline {
+            return Err(ClientError::NotCached { method: method.to_string() });
+        }
+
+        let mut attempt = 0;
+        loop {
+            let candidates = self.pool.candidates(method);
+            if candidates.is_empty() {
+                return Err(ClientError::NoEndpoint { method: method.to_string() });
+            }
+
+            let mut empty_from = None;
+            let mut history_error = None;
+            let mut transient_error: Option<ClientError> = None;
+            for endpoint in candidates {
+                endpoint.rate_limiter.acquire(method).await;
+                match self.send_request(endpoint, method, &params).await {
+                    Ok(result) if result.is_null() => {
+                        endpoint.mark_healthy();
+                        debug!("{}在{}上没有结果，尝试下一个端点", method, endpoint.name);
+                        empty_from.get_or_insert_with(|| endpoint.name.clone());
+                    }
+                    Ok(result) => {
+                        endpoint.mark_healthy();
+                        debug!("{}由{}返回", method, endpoint.name);
+                        return Ok(RpcResponse { result, endpoint: endpoint.name.clone() });
+                    }
+                    Err(e) if e.is_retryable() => {
+                        endpoint.mark_unhealthy(self.retry_after(&e).unwrap_or(self.pool.unhealthy_cooldown));
+                        if e.is_rate_limited() {
+                            warn!("{}在{}上被限流，切换端点", method, endpoint.name);
+                        } else {
+                            warn!("{}在{}上失败（{}），切换端点", method, endpoint.name, e);
+                        }
+                        // 保留建议等待时间最长的错误
+                        if transient_error.as_ref().is_none_or(|prev| e.retry_after() > prev.retry_after()) {
+                            transient_error = Some(e);
+                        }
+                    }
+                    Err(e) if e.is_history_unavailable() => {
+                        debug!("{}上没有所需的账本历史（{}），尝试下一个端点", endpoint.name, e);
+                        history_error = Some(e);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            if let Some(e) = &transient_error {
+                if attempt < self.max_retries {
+                    attempt += 1;
+                    let delay = self.retry_after(e).unwrap_or_else(|| self.backoff.delay(attempt));
+                    warn!("{}在所有端点上都失败，{}ms后第{}次重试", method, delay.as_millis(), attempt);
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            }
+
+            if let Some(endpoint) = empty_from {
+                return Ok(RpcResponse { result: Value::Null, endpoint });
+            }
+            return Err(history_error
+                .or(transient_error)
+                .expect("没有结果时至少记录了一个错误"));
+        }
+    }
+
+    /// 错误中服务器建议的重试等待时间，不超过`max_backoff_ms`，避免异常的`Retry-After`长时间阻塞
+    fn retry_after(&self, e: &ClientError) -> Option<Duration> {
+        e.retry_after().map(|delay| self.backoff.clamp(delay))
+    }
+
+    /// 向指定端点发送一次JSON-RPC请求
+    async fn send_request(
         &self,
-        signature: &str,
-    ) -> Result<Transaction, Box<dyn std::error::Error>> {
+        endpoint: &RpcEndpoint,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, ClientError> {
         let request_body = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
-            "method": "getTransaction",
-            "params": [
-                signature,
-                {
-                    "encoding": "json",
-                    "maxSupportedTransactionVersion": 0
-                }
-            ]
+            "method": method,
+            "params": params
         });
+        let json = self.send_json(endpoint, &request_body).await?;
+        rpc_result(&json)
+    }
 
+    /// 向指定端点POST请求体并解析响应JSON
+    ///
+    /// HTTP 429、非2xx状态和无法解析的响应都会转换为对应的`ClientError`。
+    async fn send_json(&self, endpoint: &RpcEndpoint, request_body: &Value) -> Result<Value, ClientError> {
         let response = self
             .client
-            .post(&self.rpc_url)
-            .json(&request_body)
+            .post(&endpoint.url)
+            .headers(endpoint.headers.clone())
+            .json(request_body)
             .send()
             .await?;
-        let json: Value = response.json().await?;
 
-        if let Some(result) = json.get("result") {
-            let mut tx: Transaction = serde_json::from_value(result.clone())?;
-            if let Some(s) = tx.transaction.signatures.first() {
-                tx.signature = s.clone();
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            // Retry-After也可能是HTTP日期格式，这里只解析秒数，其余情况使用退避时间
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(ClientError::RateLimited { retry_after });
+        }
+
+        let body = response.text().await?;
+        let json: Value = match serde_json::from_str(&body) {
+            Ok(json) => json,
+            Err(_) if !status.is_success() => {
+                return Err(ClientError::HttpStatus { status: status.as_u16(), body });
             }
-            Ok(tx)
-        } else {
-            Err(format!("Transaction not found or error in response: {}", json).into())
+            Err(e) => return Err(e.into()),
+        };
+
+        // 非2xx但带有JSON-RPC错误对象时，以错误对象为准
+        if !status.is_success() && json.get("error").is_none() {
+            return Err(ClientError::HttpStatus { status: status.as_u16(), body });
         }
+        Ok(json)
     }
 
-    /// 获取目标交易周围的交易（前4笔和后4笔交易，包含所有类型）
+    /// 批量发送同一方法的多个JSON-RPC请求，结果顺序与`params`一致
+    ///
+    /// 每`batch_size`个请求合并为一次POST。整批失败时换下一个端点；
+    /// 单个请求的结果为空、历史缺失或暂时性错误时，改为单独请求（走完整的故障转移和重试）。
+    async fn rpc_batch_request(
+        &self,
+        method: &str,
+        params: Vec<Value>,
+    ) -> Vec<Result<RpcResponse, ClientError>> {
+        if self.offline || self.batch_size <= 1 || params.len() == 1 {
+            let mut responses = Vec::with_capacity(params.len());
+            for params in params {
+                responses.push(self.rpc_request(method, params).await);
+            }
+            return responses;
+        }
+
+        let mut responses = Vec::with_capacity(params.len());
+        for chunk in params.chunks(self.batch_size) {
+            let batch = self.send_batch(method, chunk).await;
+            for (params, item) in chunk.iter().zip(batch) {
+                let response = match item {
+                    Some((Ok(result), endpoint)) if !result.is_null() => Ok(RpcResponse { result, endpoint }),
+                    Some((Err(e), _)) if !e.is_retryable() && !e.is_history_unavailable() => Err(e),
+                    _ => self.rpc_request(method, params.clone()).await,
+                };
+                responses.push(response);
+            }
+        }
+        responses
+    }
+
+    /// 发送一批请求，按优先级尝试端点直到有一个端点返回批量响应
+    ///
+    /// # 返回
+    /// 与`chunk`等长的结果，所有端点都失败或响应中缺少某个请求时对应位置为`None`
+    async fn send_batch(
+        &self,
+        method: &str,
+        chunk: &[Value],
+    ) -> Vec<Option<(Result<Value, ClientError>, String)>> {
+        let request_body: Vec<Value> = chunk
+            .iter()
+            .enumerate()
+            .map(|(id, params)| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": method,
+                    "params": params
+                })
+            })
+            .collect();
+        let request_body = Value::Array(request_body);
+
+        for endpoint in self.pool.candidates(method) {
+            for _ in chunk {
+                endpoint.rate_limiter.acquire(method).await;
+            }
+            let items = match self.send_json(endpoint, &request_body).await {
+                Ok(Value::Array(items)) => items,
+                Ok(json) => {
+                    // 不支持批量请求的节点会返回单个错误对象
+                    debug!("{}未返回批量响应: {}", endpoint.name, json);
+                    continue;
+                }
+                Err(e) => {
+                    if e.is_retryable() {
+                        endpoint.mark_unhealthy(self.retry_after(&e).unwrap_or(self.pool.unhealthy_cooldown));
+                    }
+                    warn!("批量{}在{}上失败（{}），切换端点", method, endpoint.name, e);
+                    continue;
+                }
+            };
+
+            endpoint.mark_healthy();
+            debug!("批量{} x{}由{}返回", method, chunk.len(), endpoint.name);
+            let mut results: Vec<Option<(Result<Value, ClientError>, String)>> =
+                (0..chunk.len()).map(|_| None).collect();
+            for item in &items {
+                if let Some(slot) = item
+                    .get("id")
+                    .and_then(Value::as_u64)
+                    .and_then(|id| results.get_mut(id as usize))
+                {
+                    *slot = Some((rpc_result(item), endpoint.name.clone()));
+                }
+            }
+            return results;
+        }
+
+        (0..chunk.len()).map(|_| None).collect()
+    }
+
+    /// 获取指定签名的Solana交易详情。
+    ///
+    /// # 参数
+    /// - `signature`: 交易签名（哈希）。
+    ///
+    /// # 返回
+    /// `Result`，包含`Transaction`结构体或`ClientError`。
+    pub async fn get_transaction(&self, signature: &str) -> Result<Transaction, ClientError> {
+        let response = self
+            .fetch_transactions(&[signature])
+            .await
+            .into_iter()
+            .next()
+            .unwrap_or(Err(ClientError::NotFound))?;
+        parse_transaction(response)
+    }
+
+    /// 批量获取多笔交易，结果顺序与`signatures`一致。
+    ///
+    /// # 参数
+    /// - `signatures`: 交易签名列表，按配置的`batch_size`分批请求。
+    ///
+    /// # 返回
+    /// 每笔交易各自的`Result`，单笔失败不影响其他交易。
+    pub async fn get_transactions<S: AsRef<str>>(
+        &self,
+        signatures: &[S],
+    ) -> Vec<Result<Transaction, ClientError>> {
+        self.fetch_transactions(signatures)
+            .await
+            .into_iter()
+            .map(|response| parse_transaction(response?))
+            .collect()
+    }
+
+    /// 获取多笔交易的`getTransaction`结果：先查缓存，未命中的批量请求后写入缓存
+    async fn fetch_transactions<S: AsRef<str>>(
+        &self,
+        signatures: &[S],
+    ) -> Vec<Result<RpcResponse, ClientError>> {
+        let mut responses: Vec<Option<Result<RpcResponse, ClientError>>> = signatures
+            .iter()
+            .map(|signature| {
+                self.cached_transaction(signature.as_ref()).map(|result| {
+                    Ok(RpcResponse { result, endpoint: CACHE_ENDPOINT.to_string() })
+                })
+            })
+            .collect();
+
+        let missing: Vec<usize> = (0..signatures.len())
+            .filter(|&i| responses[i].is_none())
+            .collect();
+        if missing.len() < signatures.len() {
+            debug!("{} / {} 笔交易命中缓存", signatures.len() - missing.len(), signatures.len());
+        }
+
+        if !missing.is_empty() {
+            let params = missing
+                .iter()
+                .map(|&i| transaction_params(signatures[i].as_ref(), self.commitment))
+                .collect();
+            let fetched = self.rpc_batch_request("getTransaction", params).await;
+            for (i, response) in missing.into_iter().zip(fetched) {
+                if let Ok(response) = &response {
+                    if !response.result.is_null() {
+                        self.store_transaction(signatures[i].as_ref(), &response.result);
+                    }
+                }
+                responses[i] = Some(response);
+            }
+        }
+
+        responses.into_iter().flatten().collect()
+    }
+
+    /// 按签名查询缓存：先查内存，再查磁盘（命中后放入内存）
+    fn cached_transaction(&self, signature: &str) -> Option<Value> {
+        if let Some(value) = self.cache.get_transaction(signature) {
+            return Some(value);
+        }
+        let value = self.disk_cache.as_ref()?.get_transaction(signature)?;
+        self.cache.put_transaction(signature, value.clone());
+        Some(value)
+    }
+
+    fn store_transaction(&self, signature: &str, value: &Value) {
+        self.cache.put_transaction(signature, value.clone());
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.put_transaction(signature, value);
+        }
+    }
+
+    /// 按slot查询缓存：先查内存，再查磁盘（命中后放入内存）
+    fn cached_block(&self, slot: u64) -> Option<Vec<Value>> {
+        if let Some(transactions) = self.cache.get_block(slot) {
+            return Some(transactions);
+        }
+        let transactions = self.disk_cache.as_ref()?.get_block(slot)?;
+        self.cache.put_block(slot, with_signatures(&transactions));
+        Some(transactions)
+    }
+
+    fn store_block(&self, slot: u64, transactions: &[Value]) {
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.put_block(slot, transactions);
+        }
+        self.cache.put_block(slot, with_signatures(transactions));
+    }
+
+    /// 获取目标交易周围的交易，按`(slot, 区块内位置)`排成一个序列
+    ///
+    /// 开启`skip_vote_transactions`时窗口不计投票/质押交易。
     ///
     /// # 参数
     /// - `target_signature`: 目标交易签名
+    /// - `window_transactions`: 目标交易前后各取的交易数，0表示不限制
+    /// - `window_slots`: 向前后最多扩展的slot数，只包含与目标slot同一leader的连续slot，0表示只看目标slot
     ///
     /// # 返回
     /// `Result`，包含目标交易及其周围交易的向量和目标交易在结果中的索引
     pub async fn get_nearby_transactions(
         &self,
         target_signature: &str,
-    ) -> Result<(Vec<Transaction>, usize), Box<dyn std::error::Error>> {
+        window_transactions: usize,
+        window_slots: u64,
+    ) -> Result<(Vec<Transaction>, usize), ClientError> {
         // 首先获取目标交易信息
-        let target_tx = self.get_transaction(target_signature).await?;
-        let slot = target_tx.slot;
+        let slot = self.get_transaction(target_signature).await?.slot;
 
-        // 获取完整区块
-        let all_transactions = self.get_full_block(slot).await?;
+        // 获取目标slot及同一leader相邻slot的完整区块，按slot顺序拼接
+        let mut all_transactions = Vec::new();
+        for block_slot in self.same_leader_slots(slot, window_slots).await {
+            match self.get_full_block(block_slot).await {
+                Ok(transactions) => all_transactions.extend(transactions),
+                Err(e) if block_slot == slot => return Err(e),
+                Err(e) if e.is_slot_skipped() || matches!(e, ClientError::NotFound) => {
+                    debug!("相邻slot {}没有区块", block_slot);
+                }
+                Err(e) => warn!("无法获取相邻区块{}: {}", block_slot, e),
+            }
+        }
 
-        // 找到目标交易在区块中的索引
+        // 找到目标交易在序列中的索引
         let target_index = all_transactions
             .iter()
             .position(|tx| tx.signature == target_signature)
-            .ok_or("无法在区块中找到目标交易")?;
+            .ok_or_else(|| ClientError::NotInBlock {
+                signature: target_signature.to_string(),
+                slot,
+            })?;
 
-        // 收集前4笔交易（包含所有类型）
-        let start_index = if target_index >= 4 {
-            target_index - 4
+        let (start_index, end_index) = if window_transactions == 0 {
+            (0, all_transactions.len())
         } else {
-            0
+            (
+                target_index.saturating_sub(window_transactions),
+                target_index
+                    .saturating_add(window_transactions)
+                    .saturating_add(1)
+                    .min(all_transactions.len()),
+            )
         };
-        let prev_txs = all_transactions[start_index..target_index].to_vec();
-
-        // 收集后4笔交易（包含所有类型）
-        let end_index = (target_index + 5).min(all_transactions.len());
-        let next_txs = all_transactions[(target_index + 1)..end_index].to_vec();
-
-        // 组合所有交易：前4笔 + 目标交易 + 后4笔
-        let mut nearby_transactions = Vec::new();
-        nearby_transactions.extend(prev_txs);
-        let target_index_in_result = nearby_transactions.len(); // 目标交易在结果中的索引
-        nearby_transactions.push(target_tx);
-        nearby_transactions.extend(next_txs);
+        let nearby_transactions = all_transactions[start_index..end_index].to_vec();
+        let target_index_in_result = target_index - start_index; // 目标交易在结果中的索引
 
+        let first_slot = nearby_transactions.first().map_or(slot, |tx| tx.slot);
+        let last_slot = nearby_transactions.last().map_or(slot, |tx| tx.slot);
         log::info!(
-            "获取到 {} 笔前置交易，{} 笔后置交易（包含所有类型交易）",
+            "获取到 {} 笔前置交易，{} 笔后置交易（slot {}-{}，{}）",
             target_index_in_result,
-            nearby_transactions.len() - target_index_in_result - 1
+            nearby_transactions.len() - target_index_in_result - 1,
+            first_slot,
+            last_slot,
+            if self.skip_vote_transactions { "已过滤投票交易" } else { "包含所有类型交易" }
         );
 
         Ok((nearby_transactions, target_index_in_result))
     }
 
-    /// 检查交易是否为投票交易
-    fn is_vote_transaction(&self, tx: &Transaction) -> bool {
-        // 检查账户列表中是否包含投票程序账户
-        const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
-        const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
-
-        let has_vote_account = tx
-            .transaction
-            .message
-            .account_keys
-            .iter()
-            .any(|account| account == VOTE_PROGRAM_ID || account == STAKE_PROGRAM_ID);
-
-        if has_vote_account {
-            return true;
+    /// 目标slot前后`span`个slot内与其同一leader的连续slot（升序，包含目标slot）
+    ///
+    /// 无法获取leader安排时只返回目标slot。
+    async fn same_leader_slots(&self, slot: u64, span: u64) -> Vec<u64> {
+        if span == 0 {
+            return vec![slot];
         }
 
-        // 检查程序ID（作为备用检测）
-        tx.transaction.message.instructions.iter().any(|inst| {
-            if let Some(program_id) = tx
-                .transaction
-                .message
-                .account_keys
-                .get(inst.program_id_index as usize)
-            {
-                program_id == VOTE_PROGRAM_ID || program_id == STAKE_PROGRAM_ID
-            } else {
-                false
+        let start = slot.saturating_sub(span);
+        let limit = (slot - start).saturating_add(span).saturating_add(1);
+        let params = serde_json::json!([start, limit]);
+        let leaders: Vec<String> = match self.rpc_request("getSlotLeaders", params).await {
+            Ok(response) => serde_json::from_value(response.result).unwrap_or_default(),
+            Err(e) => {
+                warn!("无法获取slot {}附近的leader安排，只分析目标slot: {}", slot, e);
+                return vec![slot];
             }
-        })
+        };
+
+        let offset = (slot - start) as usize;
+        let Some(leader) = leaders.get(offset) else {
+            return vec![slot];
+        };
+        let first = (0..offset)
+            .rev()
+            .take_while(|&i| &leaders[i] == leader)
+            .last()
+            .unwrap_or(offset);
+        let last = (offset + 1..leaders.len())
+            .take_while(|&i| &leaders[i] == leader)
+            .last()
+            .unwrap_or(offset);
+        debug!("slot {}的leader {}，相邻同leader slot: {}-{}",
+               slot, leader, start + first as u64, start + last as u64);
+        (start + first as u64..=start + last as u64).collect()
     }
 
     /// 获取指定区块的完整信息，包含所有交易详情。
@@ -229,101 +1003,192 @@ impl SolanaClient {
     /// - `slot`: 区块号。
     ///
     /// # 返回
-    /// `Result`，包含该区块所有交易的`Transaction`结构体向量或错误信息。
-    pub async fn get_full_block(
-        &self,
-        slot: u64,
-    ) -> Result<Vec<Transaction>, Box<dyn std::error::Error>> {
-        let request_body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getBlock",
-            "params": [
-                slot,
-                {
-                    "encoding": "json",
-                    "transactionDetails": "full",
-                    "maxSupportedTransactionVersion": 0
-                }
-            ]
-        });
+    /// `Result`，包含该区块所有交易的`Transaction`结构体向量或`ClientError`。
+    pub async fn get_full_block(&self, slot: u64) -> Result<Vec<Transaction>, ClientError> {
+        if let Some(cached) = self.cached_block(slot) {
+            debug!("区块{}命中缓存", slot);
+            return Ok(self.filter_block_transactions(parse_block_transactions(&cached, CACHE_ENDPOINT)));
+        }
 
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&request_body)
-            .send()
-            .await?;
-        let json: Value = response.json().await?;
-
-        if let Some(result) = json.get("result") {
-            let block_time: Option<i64> = result.get("blockTime").and_then(|v| v.as_i64());
-
-            if let Some(txs_json) = result.get("transactions") {
-                let mut transactions = Vec::new();
-                if let Some(txs_array) = txs_json.as_array() {
-                    for tx_json in txs_array {
-                        if let Some(tx_data_json) = tx_json.get("transaction") {
-                            if let Ok(tx_data) =
-                                serde_json::from_value::<TransactionData>(tx_data_json.clone())
-                            {
-                                let signature =
-                                    tx_data.signatures.first().cloned().unwrap_or_default();
-                                let tx = Transaction {
-                                    signature,
-                                    slot,
-                                    block_time,
-                                    transaction: tx_data,
-                                };
-                                transactions.push(tx);
-                            }
-                        }
-                    }
-                }
-                return Ok(transactions);
+        let params = serde_json::json!([
+            slot,
+            {
+                "encoding": "json",
+                "transactionDetails": "full",
+                "maxSupportedTransactionVersion": 0,
+                "commitment": self.commitment.for_ledger_query().as_str()
             }
+        ]);
+
+        let RpcResponse { result, endpoint } = self.rpc_request("getBlock", params).await?;
+        if result.is_null() {
+            return Err(ClientError::NotFound);
         }
 
-        Err(format!("Failed to parse full block or block not found: {}", json).into())
+        let block_time = result.get("blockTime").cloned().unwrap_or(Value::Null);
+        let txs_array: Vec<Value> = serde_json::from_value(
+            result.get("transactions").cloned().unwrap_or(Value::Null),
+        )?;
+
+        // 转换为getTransaction的结果格式（补充slot和blockTime），以便按签名缓存
+        let block_transactions: Vec<Value> = txs_array
+            .into_iter()
+            .filter_map(|mut tx_json| {
+                let object = tx_json.as_object_mut()?;
+                object.insert("slot".to_string(), Value::from(slot));
+                object.insert("blockTime".to_string(), block_time.clone());
+                Some(tx_json)
+            })
+            .collect();
+
+        let transactions = parse_block_transactions(&block_transactions, &endpoint);
+        // 缓存完整区块，保证过滤后的交易仍能得到原始位置
+        self.store_block(slot, &block_transactions);
+
+        Ok(self.filter_block_transactions(transactions))
     }
 
-    /// 获取交易的详细信息，包括余额变化
-    pub async fn get_transaction_with_balance_changes(
+    /// 按配置丢弃区块中的投票/质押交易，保留的交易仍带有原始的区块内位置
+    fn filter_block_transactions(&self, transactions: Vec<Transaction>) -> Vec<Transaction> {
+        if !self.skip_vote_transactions {
+            return transactions;
+        }
+
+        let total = transactions.len();
+        let filtered: Vec<Transaction> = transactions
+            .into_iter()
+            .filter(|tx| !is_vote_transaction(tx))
+            .collect();
+        debug!("过滤投票交易后剩余 {} / {} 笔交易", filtered.len(), total);
+        filtered
+    }
+
+    /// 查询交易的确认状态（不缓存，每次都从节点获取）
+    ///
+    /// # 参数
+    /// - `signatures`: 交易签名列表，单次最多256个。
+    ///
+    /// # 返回
+    /// 与`signatures`顺序一致的状态，节点不知道的交易（如所在分叉已被丢弃）为`None`。
+    pub async fn get_signature_statuses<S: AsRef<str>>(
         &self,
-        signature: &str,
-    ) -> Result<TransactionWithBalanceChanges, Box<dyn std::error::Error>> {
-        let request_body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getTransaction",
-            "params": [
-                signature,
-                {
-                    "encoding": "json",
-                    "maxSupportedTransactionVersion": 0,
-                    "commitment": "confirmed"
-                }
-            ]
+        signatures: &[S],
+    ) -> Result<Vec<Option<SignatureStatus>>, ClientError> {
+        let signatures: Vec<&str> = signatures.iter().map(AsRef::as_ref).collect();
+        let params = serde_json::json!([signatures, { "searchTransactionHistory": true }]);
+        let RpcResponse { result, .. } = self.rpc_request("getSignatureStatuses", params).await?;
+        let statuses = result.get("value").cloned().ok_or(ClientError::NotFound)?;
+        Ok(serde_json::from_value(statuses)?)
+    }
+
+    /// 分页获取涉及某地址的交易签名（按时间从新到旧，不缓存）
+    ///
+    /// # 参数
+    /// - `address`: 钱包或程序账户地址。
+    /// - `before`: 从这笔交易之前开始查询，`None`表示从最新的交易开始。
+    /// - `limit`: 本页最多返回的签名数（节点上限1000）。
+    ///
+    /// # 返回
+    /// 本页的签名信息，为空表示已没有更早的交易。
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &str,
+        before: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SignatureInfo>, ClientError> {
+        let mut config = serde_json::json!({
+            "limit": limit.clamp(1, MAX_SIGNATURES_PER_PAGE),
+            "commitment": self.commitment.for_ledger_query().as_str()
         });
+        if let Some(before) = before {
+            config["before"] = Value::from(before);
+        }
+        let params = serde_json::json!([address, config]);
+        let RpcResponse { result, .. } = self.rpc_request("getSignaturesForAddress", params).await?;
+        if result.is_null() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_value(result)?)
+    }
+}
 
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&request_body)
-            .timeout(Duration::from_secs(30))
-            .send()
-            .await?;
+/// `getSignaturesForAddress`单页最多返回的签名数
+pub const MAX_SIGNATURES_PER_PAGE: usize = 1000;
 
-        let json: Value = response.json().await?;
+/// 检查交易是否为投票/质押交易：每条顶层指令都调用投票或质押程序
+///
+/// 只是引用了这些程序的交易（例如在同一交易中质押的用户交易）不算，仍然保留。
+fn is_vote_transaction(tx: &Transaction) -> bool {
+    const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+    const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
 
-        if let Some(result) = json.get("result") {
-            if !result.is_null() {
-                return Ok(serde_json::from_value(result.clone())?);
-            }
+    let account_keys = tx.account_keys();
+    let instructions = &tx.transaction.message.instructions;
+    !instructions.is_empty()
+        && instructions.iter().all(|inst| {
+            account_keys
+                .get(inst.program_id_index as usize)
+                .is_some_and(|program_id| program_id == VOTE_PROGRAM_ID || program_id == STAKE_PROGRAM_ID)
+        })
+}
+
+/// `getTransaction`的请求参数
+fn transaction_params(signature: &str, commitment: Commitment) -> Value {
+    serde_json::json!([
+        signature,
+        {
+            "encoding": "json",
+            "maxSupportedTransactionVersion": 0,
+            "commitment": commitment.for_ledger_query().as_str()
         }
+    ])
+}
 
-        Err(format!("Transaction not found: {}", signature).into())
+/// 解析`getTransaction`的结果
+fn parse_transaction(response: RpcResponse) -> Result<Transaction, ClientError> {
+    if response.result.is_null() {
+        return Err(ClientError::NotFound);
     }
+    parse_transaction_value(&response.result, &response.endpoint)
+}
+
+/// 从`getTransaction`格式的JSON构造交易
+fn parse_transaction_value(result: &Value, endpoint: &str) -> Result<Transaction, ClientError> {
+    let mut tx = Transaction::deserialize(result)?;
+    if let Some(s) = tx.transaction.signatures.first() {
+        tx.signature = s.clone();
+    }
+    tx.served_by = Some(endpoint.to_string());
+    Ok(tx)
+}
+
+/// 为`getTransaction`格式的交易配上签名（用于按签名缓存）
+fn with_signatures(transactions: &[Value]) -> Vec<(String, Value)> {
+    transactions
+        .iter()
+        .filter_map(|tx| {
+            let signature = tx.pointer("/transaction/signatures/0")?.as_str()?;
+            Some((signature.to_string(), tx.clone()))
+        })
+        .collect()
+}
+
+/// 解析区块中的交易（已转换为`getTransaction`格式），跳过无法解析的交易
+fn parse_block_transactions(values: &[Value], endpoint: &str) -> Vec<Transaction> {
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| match parse_transaction_value(value, endpoint) {
+            Ok(mut tx) => {
+                tx.block_index = Some(index);
+                Some(tx)
+            }
+            Err(e) => {
+                debug!("跳过无法解析的区块交易: {}", e);
+                None
+            }
+        })
+        .collect()
 }
 
 /// 账户余额变化信息
@@ -350,27 +1215,62 @@ pub struct TokenBalanceChange {
     pub change_ui: f64,
 }
 
-/// 包含余额变化的交易信息
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct TransactionWithBalanceChanges {
-    #[serde(flatten)]
-    pub transaction: Transaction,
-    pub meta: Option<TransactionMeta>,
-}
-
-/// 交易元数据，包含余额变化信息
+/// 交易元数据（`getTransaction`/`getBlock`返回的`meta`）
+///
+/// 各列表字段在旧版本RPC中可能为`null`或缺失，统一按空列表处理。
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TransactionMeta {
     pub err: Option<Value>,
+    #[serde(default)]
     pub fee: u64,
-    #[serde(rename = "preBalances")]
+    #[serde(rename = "preBalances", default, deserialize_with = "null_as_default")]
     pub pre_balances: Vec<u64>,
-    #[serde(rename = "postBalances")]
+    #[serde(rename = "postBalances", default, deserialize_with = "null_as_default")]
     pub post_balances: Vec<u64>,
-    #[serde(rename = "preTokenBalances", default)]
+    #[serde(rename = "preTokenBalances", default, deserialize_with = "null_as_default")]
     pub pre_token_balances: Vec<TokenBalance>,
-    #[serde(rename = "postTokenBalances", default)]
+    #[serde(rename = "postTokenBalances", default, deserialize_with = "null_as_default")]
     pub post_token_balances: Vec<TokenBalance>,
+    /// v0交易通过地址查找表(ALT)加载的账户
+    #[serde(rename = "loadedAddresses", default, deserialize_with = "null_as_default")]
+    pub loaded_addresses: LoadedAddresses,
+    /// CPI产生的内部指令
+    #[serde(rename = "innerInstructions", default, deserialize_with = "null_as_default")]
+    pub inner_instructions: Vec<InnerInstructions>,
+    /// 程序日志
+    #[serde(rename = "logMessages", default, deserialize_with = "null_as_default")]
+    pub log_messages: Vec<String>,
+}
+
+/// 将`null`按默认值反序列化
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+/// `getSignaturesForAddress`返回的签名信息
+#[derive(Debug, Deserialize, Clone)]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub err: Option<Value>,
+}
+
+/// `getSignatureStatuses`返回的交易状态
+#[derive(Debug, Deserialize, Clone)]
+pub struct SignatureStatus {
+    pub slot: u64,
+    #[serde(rename = "confirmationStatus")]
+    pub confirmation_status: Option<String>,
+}
+
+impl SignatureStatus {
+    pub fn is_finalized(&self) -> bool {
+        self.confirmation_status.as_deref() == Some(Commitment::Finalized.as_str())
+    }
 }
 
 /// Token余额信息
@@ -394,3 +1294,67 @@ pub struct UiTokenAmount {
     #[serde(rename = "uiAmountString")]
     pub ui_amount_string: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn offline_client(dir: &Path, commitment: Commitment) -> SolanaClient {
+        let endpoints = [RpcEndpointConfig {
+            url: "http://127.0.0.1:8899".to_string(),
+            name: None,
+            priority: 0,
+            headers: HashMap::new(),
+            methods: Vec::new(),
+            disabled_methods: Vec::new(),
+        }];
+        let config = RpcConfig { commitment, ..RpcConfig::default() };
+        let cache = CacheConfig {
+            disk_dir: Some(dir.display().to_string()),
+            offline: true,
+            ..CacheConfig::default()
+        };
+        SolanaClient::new(&endpoints, &config, &cache).expect("应创建离线客户端")
+    }
+
+    #[test]
+    fn disk_cache_is_separated_by_commitment() {
+        let dir = std::env::temp_dir().join(format!("mev-detector-cache-{}", std::process::id()));
+        let value = serde_json::json!({ "slot": 1 });
+        offline_client(&dir, Commitment::Confirmed).store_transaction("abc", &value);
+
+        assert_eq!(offline_client(&dir, Commitment::Confirmed).cached_transaction("abc"), Some(value));
+        // processed按confirmed查询账本，共用同一目录
+        assert!(offline_client(&dir, Commitment::Processed).cached_transaction("abc").is_some());
+        assert!(offline_client(&dir, Commitment::Finalized).cached_transaction("abc").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn vote_filter_only_drops_pure_vote_and_stake_transactions() {
+        use crate::decoders::test_support::{pubkey, transaction};
+        const VOTE: &str = "Vote111111111111111111111111111111111111111";
+        const STAKE: &str = "Stake11111111111111111111111111111111111111";
+
+        assert!(is_vote_transaction(&transaction(VOTE, &[pubkey(1), pubkey(2)], &[2], &[])));
+        assert!(is_vote_transaction(&transaction(STAKE, &[pubkey(1), pubkey(2)], &[2], &[])));
+
+        // 只引用了质押程序账户的swap
+        let swap = transaction(
+            "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+            &[pubkey(1), STAKE.to_string()],
+            &[9],
+            &[],
+        );
+        assert!(!is_vote_transaction(&swap));
+
+        // 质押指令之外还调用了其他程序
+        let mut stake_and_other = transaction(STAKE, &[pubkey(1), pubkey(2)], &[2], &[]);
+        let mut other = stake_and_other.transaction.message.instructions[0].clone();
+        other.program_id_index = 1;
+        stake_and_other.transaction.message.instructions.push(other);
+        assert!(!is_vote_transaction(&stake_and_other));
+    }
+}
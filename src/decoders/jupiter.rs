@@ -0,0 +1,67 @@
+use super::{infer_token_from_accounts, InstructionContext, SwapDecoder};
+use crate::mev::program_ids::JUPITER;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Jupiter";
+
+/// Jupiter聚合器解码器
+pub struct JupiterDecoder;
+
+impl SwapDecoder for JupiterDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[JUPITER]
+    }
+
+    fn is_aggregator(&self) -> bool {
+        true
+    }
+
+    /// Jupiter是聚合器，指令格式复杂，只解析基本的swap信息
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_route(ctx).into_iter().collect()
+    }
+}
+
+fn decode_route(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    if data.len() < 17 {
+        return None;
+    }
+
+    let amount_in = u64::from_le_bytes(data[1..9].try_into().ok()?);
+
+    let user_address = ctx.account(0)?;
+    let pool_address = ctx.account(1).or_else(|| ctx.account_keys.first().cloned())?;
+
+    let token_in = infer_token_from_accounts(&ctx.instruction.accounts, ctx.account_keys, true)?;
+    let token_out = infer_token_from_accounts(&ctx.instruction.accounts, ctx.account_keys, false)?;
+
+    debug!("Jupiter swap: {} -> {}, amount_in: {}",
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out: 0, // Jupiter可能不直接提供预期输出
+        user_address,
+        pool_address,
+        amount_specified_is_input: true,
+        other_amount_threshold: 0,
+        user_source: None,
+        user_destination: None,
+        vault_in: None,
+        vault_out: None,
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
@@ -0,0 +1,110 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::METEORA_DYNAMIC_AMM;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Meteora Dynamic AMM";
+
+const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// sha256("event:Swap")的前8字节
+const SWAP_EVENT: [u8; 8] = [81, 108, 227, 190, 205, 208, 10, 196];
+
+const POOL_ACCOUNT: usize = 0;
+const USER_SOURCE_ACCOUNT: usize = 1;
+const USER_DESTINATION_ACCOUNT: usize = 2;
+/// 金库程序中保存代币的账户，按mint在多个池子间共享
+const A_TOKEN_VAULT_ACCOUNT: usize = 5;
+const B_TOKEN_VAULT_ACCOUNT: usize = 6;
+const USER_ACCOUNT: usize = 12;
+
+/// Meteora动态AMM解码器
+pub struct MeteoraAmmDecoder;
+
+impl SwapDecoder for MeteoraAmmDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[METEORA_DYNAMIC_AMM]
+    }
+
+    /// 解析swap指令：`in_amount`, `minimum_out_amount`
+    ///
+    /// 账户: 0 pool, 1 user_source_token, 2 user_destination_token, 3 a_vault, 4 b_vault,
+    /// 5 a_token_vault, 6 b_token_vault, 7-8 vault_lp_mint, 9-10 vault_lp,
+    /// 11 protocol_token_fee, 12 user, ...
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&SWAP_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        // 布局: in_amount, out_amount, trade_fee, protocol_fee, host_fee（不包含池子地址）
+        let event = SwapEvent::new(reader.read_u64()?, reader.read_u64()?);
+        debug!("解码到Meteora动态AMM事件: {:?}", event);
+        Some(event)
+    }
+
+    /// 金库由同一代币的所有池子共享，只有池子账户本身能说明两笔交易作用于同一池子
+    fn pool_accounts(&self, swap: &SwapInstructionData) -> Vec<String> {
+        vec![swap.pool_address.clone()]
+    }
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    if data.get(..8)? != SWAP {
+        return None;
+    }
+
+    let mut reader = BorshReader::new(&data[8..]);
+    let amount_in = reader.read_u64()?;
+    let minimum_amount_out = reader.read_u64()?;
+
+    let mint_a = ctx.token_mint(A_TOKEN_VAULT_ACCOUNT)?;
+    let mint_b = ctx.token_mint(B_TOKEN_VAULT_ACCOUNT)?;
+
+    // 方向：优先看用户源账户的mint，其次看a金库余额是否增加
+    let a_to_b = match ctx.token_mint(USER_SOURCE_ACCOUNT) {
+        Some(mint) if mint == mint_a => true,
+        Some(mint) if mint == mint_b => false,
+        _ => ctx.token_amount_change(A_TOKEN_VAULT_ACCOUNT).is_some_and(|change| change > 0),
+    };
+
+    let a_token_vault = ctx.account(A_TOKEN_VAULT_ACCOUNT)?;
+    let b_token_vault = ctx.account(B_TOKEN_VAULT_ACCOUNT)?;
+    let (token_in, token_out, vault_in, vault_out) = if a_to_b {
+        (mint_a, mint_b, a_token_vault, b_token_vault)
+    } else {
+        (mint_b, mint_a, b_token_vault, a_token_vault)
+    };
+
+    debug!("Meteora动态AMM swap: {} -> {}, amount_in: {}, 最少输出: {}",
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, minimum_amount_out);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out: minimum_amount_out,
+        user_address: ctx.account(USER_ACCOUNT)?,
+        pool_address: ctx.account(POOL_ACCOUNT)?,
+        amount_specified_is_input: true,
+        other_amount_threshold: minimum_amount_out,
+        user_source: ctx.account(USER_SOURCE_ACCOUNT),
+        user_destination: ctx.account(USER_DESTINATION_ACCOUNT),
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
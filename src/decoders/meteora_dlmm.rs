@@ -0,0 +1,231 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::METEORA_DLMM;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Meteora DLMM";
+
+/// swap类指令的参数布局
+#[derive(Debug, Clone, Copy)]
+enum SwapArgs {
+    /// `amount_in`, `min_amount_out`
+    ExactIn,
+    /// `max_in_amount`, `out_amount`
+    ExactOut,
+    /// `amount_in`, `active_id`, `max_price_impact_bps`，以价格影响作为滑点边界，没有最少输出参数
+    PriceImpact,
+}
+
+/// swap类指令：名称、鉴别器、参数布局
+const SWAP_INSTRUCTIONS: [(&str, [u8; 8], SwapArgs); 6] = [
+    ("swap", [248, 198, 158, 145, 225, 117, 135, 200], SwapArgs::ExactIn),
+    ("swap2", [65, 75, 63, 76, 235, 91, 91, 136], SwapArgs::ExactIn),
+    ("swap_exact_out", [250, 73, 101, 33, 38, 207, 75, 184], SwapArgs::ExactOut),
+    ("swap_exact_out2", [43, 215, 247, 132, 137, 60, 243, 81], SwapArgs::ExactOut),
+    ("swap_with_price_impact", [56, 173, 230, 208, 173, 228, 156, 205], SwapArgs::PriceImpact),
+    ("swap_with_price_impact2", [74, 98, 192, 214, 177, 51, 75, 51], SwapArgs::PriceImpact),
+];
+
+/// sha256("event:Swap")的前8字节
+const SWAP_EVENT: [u8; 8] = [81, 108, 227, 190, 205, 208, 10, 196];
+
+// swap系列指令共有的账户位置
+const LB_PAIR_ACCOUNT: usize = 0;
+const RESERVE_X_ACCOUNT: usize = 2;
+const RESERVE_Y_ACCOUNT: usize = 3;
+const USER_TOKEN_IN_ACCOUNT: usize = 4;
+const USER_TOKEN_OUT_ACCOUNT: usize = 5;
+const TOKEN_X_MINT_ACCOUNT: usize = 6;
+const TOKEN_Y_MINT_ACCOUNT: usize = 7;
+const USER_ACCOUNT: usize = 10;
+
+/// Meteora DLMM `Swap`事件中成交数量以外的字段
+#[derive(Debug, Clone)]
+pub struct MeteoraDlmmSwap {
+    /// true为X -> Y
+    pub swap_for_y: bool,
+}
+
+/// Meteora DLMM解码器
+pub struct MeteoraDlmmDecoder;
+
+impl SwapDecoder for MeteoraDlmmDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[METEORA_DLMM]
+    }
+
+    /// 解析swap / swap_exact_out / swap_with_price_impact及其v2版本
+    ///
+    /// - swap: `amount_in`, `min_amount_out`
+    /// - swap_exact_out: `max_in_amount`, `out_amount`
+    /// - swap_with_price_impact: `amount_in`, `active_id`, `max_price_impact_bps`
+    ///
+    /// 方向由用户输入账户的mint与交易对的token_x_mint比较得出。
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&SWAP_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        // 布局: lb_pair, from, start_bin_id(i32), end_bin_id(i32),
+        // amount_in, amount_out, swap_for_y, fee, protocol_fee, fee_bps, host_fee
+        let lb_pair = reader.read_pubkey()?;
+        let amount_in = reader.skip(32 + 4 + 4).read_u64()?;
+        let amount_out = reader.read_u64()?;
+        let swap_for_y = reader.read_bool()?;
+        let event = SwapEvent::new(amount_in, amount_out)
+            .with_pool(lb_pair)
+            .with_venue_data(MeteoraDlmmSwap { swap_for_y });
+        debug!("解码到Meteora DLMM事件: {:?}", event);
+        Some(event)
+    }
+
+    fn apply_event(&self, swap: &mut SwapInstructionData, event: &SwapEvent) {
+        if let Some(e) = event.venue_data::<MeteoraDlmmSwap>() {
+            debug!("Meteora DLMM Swap: swap_for_y={}, {} -> {}",
+                   e.swap_for_y, get_token_symbol(&swap.token_in), get_token_symbol(&swap.token_out));
+        }
+    }
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let discriminator = data.get(..8)?;
+    let &(name, _, args) = SWAP_INSTRUCTIONS
+        .iter()
+        .find(|(_, d, _)| d.as_slice() == discriminator)?;
+
+    // 未被指定的一侧此时是滑点边界（价格影响类指令为0），之后由Swap事件或余额变化修正
+    let mut reader = BorshReader::new(&data[8..]);
+    let (amount_in, amount_out, other_amount_threshold) = match args {
+        SwapArgs::ExactIn => {
+            let amount_in = reader.read_u64()?;
+            let min_amount_out = reader.read_u64()?;
+            (amount_in, min_amount_out, min_amount_out)
+        }
+        SwapArgs::ExactOut => {
+            let max_in_amount = reader.read_u64()?;
+            let out_amount = reader.read_u64()?;
+            (max_in_amount, out_amount, max_in_amount)
+        }
+        SwapArgs::PriceImpact => (reader.read_u64()?, 0, 0),
+    };
+    let exact_in = !matches!(args, SwapArgs::ExactOut);
+
+    let mint_x = ctx.account(TOKEN_X_MINT_ACCOUNT)?;
+    let mint_y = ctx.account(TOKEN_Y_MINT_ACCOUNT)?;
+
+    // 方向：优先看用户输入账户的mint，其次看reserve_x余额是否增加
+    let swap_for_y = match ctx.token_mint(USER_TOKEN_IN_ACCOUNT) {
+        Some(mint) if mint == mint_x => true,
+        Some(mint) if mint == mint_y => false,
+        _ => ctx.token_amount_change(RESERVE_X_ACCOUNT).is_some_and(|change| change > 0),
+    };
+
+    let reserve_x = ctx.account(RESERVE_X_ACCOUNT)?;
+    let reserve_y = ctx.account(RESERVE_Y_ACCOUNT)?;
+    let (token_in, token_out, vault_in, vault_out) = if swap_for_y {
+        (mint_x, mint_y, reserve_x, reserve_y)
+    } else {
+        (mint_y, mint_x, reserve_y, reserve_x)
+    };
+
+    debug!("Meteora DLMM {}: {} -> {}, in: {}, out: {}",
+           name, get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address: ctx.account(USER_ACCOUNT)?,
+        pool_address: ctx.account(LB_PAIR_ACCOUNT)?,
+        amount_specified_is_input: exact_in,
+        other_amount_threshold,
+        user_source: ctx.account(USER_TOKEN_IN_ACCOUNT),
+        user_destination: ctx.account(USER_TOKEN_OUT_ACCOUNT),
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const MINT_X: u8 = 7;
+    const MINT_Y: u8 = 8;
+
+    /// 用户输入账户持有token_x（X -> Y）
+    fn swap_transaction(name: &str, args: &[u8]) -> crate::client::Transaction {
+        let (_, discriminator, _) = SWAP_INSTRUCTIONS.iter().find(|(n, _, _)| *n == name).unwrap();
+        let mut data = discriminator.to_vec();
+        data.extend(args);
+        let accounts: Vec<String> = (1..=16).map(pubkey).collect();
+        let mint_x = pubkey(MINT_X);
+        transaction(METEORA_DLMM, &accounts, &data, &[(USER_TOKEN_IN_ACCOUNT, &mint_x, 10_000, 0)])
+    }
+
+    fn amounts(first: u64, second: u64) -> Vec<u8> {
+        first.to_le_bytes().into_iter().chain(second.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn decodes_exact_in_swaps() {
+        for name in ["swap", "swap2"] {
+            let swaps = decode(&MeteoraDlmmDecoder, &swap_transaction(name, &amounts(10_000, 9_000)));
+            assert_eq!(swaps.len(), 1, "{}", name);
+            let swap = &swaps[0];
+            assert!(swap.amount_specified_is_input);
+            assert_eq!((swap.amount_in, swap.amount_out), (10_000, 9_000));
+            assert_eq!(swap.other_amount_threshold, 9_000);
+            assert_eq!(swap.token_in, pubkey(MINT_X));
+            assert_eq!(swap.token_out, pubkey(MINT_Y));
+            assert_eq!(swap.pool_address, pubkey(1));
+            assert_eq!(swap.user_address, pubkey(11));
+            assert_eq!(swap.vault_in, Some(pubkey(3)));
+            assert_eq!(swap.vault_out, Some(pubkey(4)));
+        }
+    }
+
+    #[test]
+    fn decodes_exact_out_swaps() {
+        // max_in_amount, out_amount
+        for name in ["swap_exact_out", "swap_exact_out2"] {
+            let swaps = decode(&MeteoraDlmmDecoder, &swap_transaction(name, &amounts(10_500, 10_000)));
+            assert_eq!(swaps.len(), 1, "{}", name);
+            let swap = &swaps[0];
+            assert!(!swap.amount_specified_is_input);
+            assert_eq!((swap.amount_in, swap.amount_out), (10_500, 10_000));
+            assert_eq!(swap.other_amount_threshold, 10_500);
+        }
+    }
+
+    #[test]
+    fn decodes_price_impact_swap() {
+        // amount_in, active_id: Some(-5), max_price_impact_bps: 50
+        let mut args = 10_000u64.to_le_bytes().to_vec();
+        args.push(1);
+        args.extend((-5i32).to_le_bytes());
+        args.extend(50u16.to_le_bytes());
+        let swaps = decode(&MeteoraDlmmDecoder, &swap_transaction("swap_with_price_impact", &args));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert!(swap.amount_specified_is_input);
+        assert_eq!((swap.amount_in, swap.amount_out, swap.other_amount_threshold), (10_000, 0, 0));
+    }
+}
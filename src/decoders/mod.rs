@@ -0,0 +1,375 @@
+//! DEX swap解码器
+//!
+//! 每个DEX实现一个[`SwapDecoder`]，由[`DecoderRegistry`]按程序ID统一管理。
+//! `MevDetector`识别DEX交易、解析swap指令、解码日志事件时都通过注册表查找解码器，
+//! 新增交易场所（包括已有程序的分叉）只需实现该trait并注册，无需修改检测逻辑或事件类型：
+//! 场所名称由解码器给出，日志事件按解码器归属与swap指令对应，场所专有的事件数据挂在[`SwapEvent`]上。
+
+mod jupiter;
+mod meteora_amm;
+mod meteora_dlmm;
+mod openbook_v2;
+mod orca_v1;
+mod phoenix;
+mod pump_fun;
+mod raydium_amm;
+mod raydium_clmm;
+mod raydium_cpmm;
+mod whirlpool;
+
+pub use jupiter::JupiterDecoder;
+pub use meteora_amm::MeteoraAmmDecoder;
+pub use meteora_dlmm::MeteoraDlmmDecoder;
+pub use openbook_v2::OpenBookV2Decoder;
+pub use orca_v1::OrcaV1Decoder;
+pub use phoenix::PhoenixDecoder;
+pub use pump_fun::{PumpFunDecoder, PumpFunTradeEvent};
+pub use raydium_amm::RaydiumAmmDecoder;
+pub use raydium_clmm::RaydiumClmmDecoder;
+pub use raydium_cpmm::RaydiumCpmmDecoder;
+pub use whirlpool::WhirlpoolDecoder;
+
+use crate::client::{Instruction, Transaction};
+use crate::logs::{self, ProgramLogKind, SwapEvent};
+use crate::mev::token_info::*;
+use crate::mev::SwapInstructionData;
+use log::debug;
+use std::collections::HashMap;
+
+/// 单个DEX程序的swap解码器
+pub trait SwapDecoder: Send + Sync {
+    /// 解码器名称，同时作为其解析出的swap的场所名称（`SwapInstructionData::venue`），
+    /// 以及日志事件的归属标识，需在注册表中唯一
+    fn name(&self) -> &'static str;
+
+    /// 该解码器负责的程序ID
+    fn program_ids(&self) -> &[&str];
+
+    /// 是否为聚合器。聚合器指令只能粗略解析，
+    /// 若其CPI中已解析出具体DEX的swap，则以内部指令为准
+    fn is_aggregator(&self) -> bool {
+        false
+    }
+
+    /// 解析一条指令中的swap（多跳指令可返回多个，非swap指令返回空）
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData>;
+
+    /// 解码`Program log: `文本日志中的swap事件
+    fn decode_log_message(&self, _message: &str) -> Option<SwapEvent> {
+        None
+    }
+
+    /// 解码Anchor事件数据（8字节鉴别器 + borsh数据），
+    /// 来源可以是`Program data: `日志或`emit_cpi!`自调用指令
+    fn decode_event(&self, _data: &[u8]) -> Option<SwapEvent> {
+        None
+    }
+
+    /// 判断日志事件是否属于该swap指令（调用前已保证事件由该解码器从同一程序的日志中解码）
+    fn event_matches(&self, event: &SwapEvent, swap: &SwapInstructionData) -> bool {
+        event.pool.as_ref().is_none_or(|pool| *pool == swap.pool_address)
+    }
+
+    /// 在使用事件中的成交数量覆盖之后，补充修正其他字段
+    fn apply_event(&self, _swap: &mut SwapInstructionData, _event: &SwapEvent) {}
+
+    /// swap中会被同一池子的其他交易共享的账户（池子、金库等）
+    fn pool_accounts(&self, swap: &SwapInstructionData) -> Vec<String> {
+        std::iter::once(swap.pool_address.clone())
+            .chain(swap.vault_in.clone())
+            .chain(swap.vault_out.clone())
+            .collect()
+    }
+}
+
+/// 解码一条指令时可用的上下文
+pub struct InstructionContext<'a> {
+    pub tx: &'a Transaction,
+    pub instruction: &'a Instruction,
+    /// 已解析地址查找表的完整账户列表
+    pub account_keys: &'a [String],
+    pub program_id: &'a str,
+}
+
+impl InstructionContext<'_> {
+    /// 解码指令数据
+    pub fn data(&self) -> Option<Vec<u8>> {
+        bs58::decode(&self.instruction.data).into_vec().ok()
+    }
+
+    /// 指令中第`position`个账户在交易账户列表中的索引
+    pub fn account_index(&self, position: usize) -> Option<usize> {
+        self.instruction.accounts.get(position).map(|&index| index as usize)
+    }
+
+    /// 指令中第`position`个账户的地址
+    pub fn account(&self, position: usize) -> Option<String> {
+        self.account_keys.get(self.account_index(position)?).cloned()
+    }
+
+    /// 指令中第`position`个代币账户的mint（来自pre/postTokenBalances）
+    pub fn token_mint(&self, position: usize) -> Option<String> {
+        self.tx.token_mint(self.account_index(position)?).map(str::to_string)
+    }
+
+    /// 指令中第`position`个代币账户在交易前后的余额变化
+    pub fn token_amount_change(&self, position: usize) -> Option<i128> {
+        self.tx.token_amount_change(self.account_index(position)?)
+    }
+}
+
+/// 按程序ID索引的解码器注册表
+pub struct DecoderRegistry {
+    decoders: Vec<Box<dyn SwapDecoder>>,
+    by_program: HashMap<String, usize>,
+}
+
+impl DecoderRegistry {
+    /// 创建空注册表
+    pub fn empty() -> Self {
+        Self {
+            decoders: Vec::new(),
+            by_program: HashMap::new(),
+        }
+    }
+
+    /// 注册解码器，程序ID与已注册的解码器重复时由新解码器接管
+    pub fn register(&mut self, decoder: Box<dyn SwapDecoder>) {
+        let index = self.decoders.len();
+        for program_id in decoder.program_ids() {
+            if let Some(previous) = self.by_program.insert(program_id.to_string(), index) {
+                debug!("程序{}的解码器由{}替换为{}",
+                       program_id, self.decoders[previous].name(), decoder.name());
+            }
+        }
+        self.decoders.push(decoder);
+    }
+
+    /// 查找负责该程序的解码器
+    pub fn get(&self, program_id: &str) -> Option<&dyn SwapDecoder> {
+        self.by_program
+            .get(program_id)
+            .map(|&index| self.decoders[index].as_ref())
+    }
+
+    /// 是否为已注册的DEX程序
+    pub fn is_dex_program(&self, program_id: &str) -> bool {
+        self.by_program.contains_key(program_id)
+    }
+
+    /// 从交易日志中解码所有已注册DEX的swap事件（按日志顺序）
+    pub fn decode_swap_events(&self, log_messages: &[String]) -> Vec<SwapEvent> {
+        logs::parse_program_logs(log_messages)
+            .iter()
+            .filter_map(|log| {
+                let decoder = self.get(&log.program_id)?;
+                let event = match &log.kind {
+                    ProgramLogKind::Message(message) => decoder.decode_log_message(message),
+                    ProgramLogKind::Data(data) => decoder.decode_event(data),
+                }?;
+                Some(attribute_event(event, &log.program_id, decoder))
+            })
+            .collect()
+    }
+
+    /// 解码Anchor `emit_cpi!`产生的自调用内部指令中的事件
+    pub fn decode_cpi_event(&self, program_id: &str, instruction_data: &str) -> Option<SwapEvent> {
+        let payload = logs::cpi_event_payload(instruction_data)?;
+        let decoder = self.get(program_id)?;
+        let event = decoder.decode_event(&payload)?;
+        Some(attribute_event(event, program_id, decoder))
+    }
+}
+
+impl Default for DecoderRegistry {
+    /// 包含所有内置DEX解码器的注册表
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(RaydiumAmmDecoder));
+        registry.register(Box::new(RaydiumClmmDecoder));
+        registry.register(Box::new(RaydiumCpmmDecoder));
+        registry.register(Box::new(WhirlpoolDecoder));
+        registry.register(Box::new(OrcaV1Decoder));
+        registry.register(Box::new(JupiterDecoder));
+        registry.register(Box::new(PumpFunDecoder));
+        registry.register(Box::new(MeteoraDlmmDecoder));
+        registry.register(Box::new(MeteoraAmmDecoder));
+        registry.register(Box::new(PhoenixDecoder));
+        registry.register(Box::new(OpenBookV2Decoder));
+        registry
+    }
+}
+
+/// 记录事件来自哪个程序、由哪个解码器解码
+fn attribute_event(mut event: SwapEvent, program_id: &str, decoder: &dyn SwapDecoder) -> SwapEvent {
+    event.program_id = program_id.to_string();
+    event.decoder = decoder.name();
+    event
+}
+
+/// 从账户列表推断token地址（用于无法精确解析账户布局的程序）
+fn infer_token_from_accounts(
+    accounts: &[u8],
+    account_keys: &[String],
+    is_input: bool
+) -> Option<String> {
+    // 根据账户位置推断token
+    // 通常input token在前面的位置，output token在后面
+    let start_idx = if is_input { 2 } else { 4 };
+    let end_idx = if is_input { 6 } else { 8 };
+
+    for i in start_idx..end_idx.min(accounts.len()) {
+        if let Some(account) = account_keys.get(accounts[i] as usize) {
+            // 检查是否是已知的token地址
+            if is_known_token(account) {
+                return Some(account.clone());
+            }
+        }
+    }
+
+    // 如果没找到已知token，返回第一个可能的token账户
+    if let Some(account_idx) = accounts.get(start_idx) {
+        account_keys.get(*account_idx as usize).cloned()
+    } else {
+        None
+    }
+}
+
+/// 检查是否是已知的token
+fn is_known_token(address: &str) -> bool {
+    matches!(address,
+        WSOL | USDC | USDT | RAY | BONK | WIF |
+        "11111111111111111111111111111111" | // System program
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" // Token program
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::program_ids::{ORCA_WHIRLPOOLS, PUMP_FUN};
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    #[test]
+    fn decodes_program_data_events_and_attributes_them() {
+        let traded = whirlpool::tests::traded_event(false, 5_000, 4_900);
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", ORCA_WHIRLPOOLS),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program data: {}", BASE64.encode(&traded)),
+            format!("Program {} success", ORCA_WHIRLPOOLS),
+            // 未注册程序输出的相同事件不解码
+            "Program Unknown1111111111111111111111111111111 invoke [1]".to_string(),
+            format!("Program data: {}", BASE64.encode(&traded)),
+            "Program Unknown1111111111111111111111111111111 success".to_string(),
+        ];
+
+        let events = DecoderRegistry::default().decode_swap_events(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].program_id, ORCA_WHIRLPOOLS);
+        assert_eq!(events[0].decoder, "Orca Whirlpool");
+        assert_eq!((events[0].amount_in, events[0].amount_out), (5_000, 4_900));
+    }
+
+    #[test]
+    fn decodes_emit_cpi_events() {
+        let mut data = vec![0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+        data.extend(pump_fun::tests::trade_event(true, 100, 200));
+        let instruction_data = bs58::encode(data).into_string();
+
+        let event = DecoderRegistry::default()
+            .decode_cpi_event(PUMP_FUN, &instruction_data)
+            .expect("应解码TradeEvent");
+        assert_eq!(event.program_id, PUMP_FUN);
+        assert_eq!(event.decoder, "Pump.fun");
+        assert_eq!((event.amount_in, event.amount_out), (100, 200));
+    }
+}
+
+/// 解码器测试用的交易构造工具
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::{InstructionContext, SwapDecoder};
+    use crate::client::Transaction;
+    use crate::mev::SwapInstructionData;
+    use serde_json::json;
+
+    pub(crate) use super::pump_fun::tests::trade_with_event as pump_trade_with_event;
+
+    /// 测试用的地址（32字节全为`n`）
+    pub fn pubkey(n: u8) -> String {
+        bs58::encode([n; 32]).into_string()
+    }
+
+    /// 构造只含一条顶层指令的交易
+    ///
+    /// 指令按顺序引用`accounts`，程序ID放在账户列表最后。
+    /// `balances`为代币余额`(指令中的账户位置, mint, 交易前数量, 交易后数量)`。
+    pub fn transaction(
+        program_id: &str,
+        accounts: &[String],
+        data: &[u8],
+        balances: &[(usize, &str, u64, u64)],
+    ) -> Transaction {
+        let mut account_keys = accounts.to_vec();
+        account_keys.push(program_id.to_string());
+        let token_balances = |post: bool| -> Vec<serde_json::Value> {
+            balances
+                .iter()
+                .map(|&(index, mint, pre_amount, post_amount)| {
+                    let amount = if post { post_amount } else { pre_amount };
+                    json!({
+                        "accountIndex": index,
+                        "mint": mint,
+                        "owner": null,
+                        "uiTokenAmount": {
+                            "amount": amount.to_string(),
+                            "decimals": 6,
+                            "uiAmount": null,
+                            "uiAmountString": amount.to_string(),
+                        },
+                    })
+                })
+                .collect()
+        };
+
+        serde_json::from_value(json!({
+            "signature": "test",
+            "slot": 1,
+            "blockTime": null,
+            "transaction": {
+                "signatures": ["test"],
+                "message": {
+                    "accountKeys": account_keys,
+                    "instructions": [{
+                        "programIdIndex": accounts.len(),
+                        "accounts": (0..accounts.len()).collect::<Vec<_>>(),
+                        "data": bs58::encode(data).into_string(),
+                    }],
+                    "recentBlockhash": null,
+                    "header": null,
+                },
+            },
+            "meta": {
+                "err": null,
+                "preTokenBalances": token_balances(false),
+                "postTokenBalances": token_balances(true),
+            },
+        }))
+        .expect("测试交易格式错误")
+    }
+
+    /// 用解码器解析交易中的第一条指令
+    pub fn decode(decoder: &dyn SwapDecoder, tx: &Transaction) -> Vec<SwapInstructionData> {
+        let account_keys = tx.account_keys();
+        let instruction = &tx.transaction.message.instructions[0];
+        let ctx = InstructionContext {
+            tx,
+            instruction,
+            account_keys: &account_keys,
+            program_id: &account_keys[instruction.program_id_index as usize],
+        };
+        decoder.decode_instruction(&ctx)
+    }
+}
@@ -0,0 +1,332 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::BorshReader;
+use crate::mev::program_ids::OPENBOOK_V2;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "OpenBook v2";
+
+const PLACE_TAKE_ORDER: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+const PLACE_ORDER: [u8; 8] = [51, 194, 155, 175, 109, 130, 96, 106];
+
+/// 批量下单、改单与撤单指令，不作为swap处理
+const ORDER_MANAGEMENT_INSTRUCTIONS: [(&str, [u8; 8]); 6] = [
+    ("place_orders", [60, 63, 50, 123, 12, 197, 60, 190]),
+    ("edit_order", [254, 208, 118, 29, 173, 248, 200, 70]),
+    ("cancel_order", [95, 129, 237, 240, 8, 49, 223, 132]),
+    ("cancel_order_by_client_order_id", [115, 178, 201, 8, 175, 183, 123, 119]),
+    ("cancel_all_orders", [196, 83, 243, 171, 17, 100, 160, 143]),
+    ("cancel_all_and_place_orders", [128, 155, 222, 60, 186, 40, 225, 50]),
+];
+
+const SIDE_BID: u8 = 0;
+
+// PlaceOrderType中立即成交的类型
+const ORDER_IMMEDIATE_OR_CANCEL: u8 = 1;
+const ORDER_MARKET: u8 = 3;
+
+// place_take_order账户: 0 signer, 1 penalty_payer, 2 market, 3 market_authority, 4 bids, 5 asks,
+// 6 market_base_vault, 7 market_quote_vault, 8 event_heap, 9 user_base_account, 10 user_quote_account, ...
+const SIGNER_ACCOUNT: usize = 0;
+const MARKET_ACCOUNT: usize = 2;
+const MARKET_BASE_VAULT_ACCOUNT: usize = 6;
+const MARKET_QUOTE_VAULT_ACCOUNT: usize = 7;
+const USER_BASE_ACCOUNT: usize = 9;
+const USER_QUOTE_ACCOUNT: usize = 10;
+
+// place_order账户: 0 signer, 1 open_orders_account, 2 open_orders_admin, 3 user_token_account,
+// 4 market, 5 bids, 6 asks, 7 event_heap, 8 market_vault, ...
+const PLACE_ORDER_MARKET_ACCOUNT: usize = 4;
+const PLACE_ORDER_USER_TOKEN_ACCOUNT: usize = 3;
+const PLACE_ORDER_VAULT_ACCOUNT: usize = 8;
+
+/// OpenBook v2订单簿解码器
+///
+/// 订单簿的共享可写账户是market（以及bids/asks/event_heap），对应`pool_address`。
+/// 订单数量以lot为单位，实际成交数量由金库余额变化确定。
+pub struct OpenBookV2Decoder;
+
+impl SwapDecoder for OpenBookV2Decoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[OPENBOOK_V2]
+    }
+
+    /// 解析place_take_order（立即成交并直接结算到用户代币账户），
+    /// 以及订单类型为ImmediateOrCancel或Market的place_order（吃单，成交记入open orders账户）
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        let Some(discriminator) = ctx.data().and_then(|data| data.get(..8).map(<[u8]>::to_vec)) else {
+            return Vec::new();
+        };
+        let swap = if discriminator == PLACE_TAKE_ORDER {
+            decode_take_order(ctx)
+        } else if discriminator == PLACE_ORDER {
+            decode_place_order(ctx)
+        } else {
+            if let Some((name, _)) = ORDER_MANAGEMENT_INSTRUCTIONS
+                .iter()
+                .find(|(_, d)| d.as_slice() == discriminator)
+            {
+                debug!("OpenBook v2订单管理指令: {}，不作为swap处理", name);
+            }
+            None
+        };
+        swap.into_iter().collect()
+    }
+}
+
+/// 参数: side, price_lots, max_base_lots, max_quote_lots_including_fees, order_type, limit
+fn decode_take_order(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let mut reader = BorshReader::new(&data[8..]);
+    let is_bid = reader.read_u8()? == SIDE_BID;
+    let price_lots = reader.read_i64()?;
+    let max_base_lots = reader.read_i64()?;
+    let max_quote_lots = reader.read_i64()?;
+
+    let base_mint = ctx
+        .token_mint(MARKET_BASE_VAULT_ACCOUNT)
+        .or_else(|| ctx.token_mint(USER_BASE_ACCOUNT))?;
+    let quote_mint = ctx
+        .token_mint(MARKET_QUOTE_VAULT_ACCOUNT)
+        .or_else(|| ctx.token_mint(USER_QUOTE_ACCOUNT))?;
+    let base_vault = ctx.account(MARKET_BASE_VAULT_ACCOUNT)?;
+    let quote_vault = ctx.account(MARKET_QUOTE_VAULT_ACCOUNT)?;
+
+    // 买单支付quote得到base，卖单相反
+    let (token_in, token_out, vault_in, vault_out, user_source, user_destination) = if is_bid {
+        (quote_mint, base_mint, quote_vault, base_vault,
+         ctx.account(USER_QUOTE_ACCOUNT), ctx.account(USER_BASE_ACCOUNT))
+    } else {
+        (base_mint, quote_mint, base_vault, quote_vault,
+         ctx.account(USER_BASE_ACCOUNT), ctx.account(USER_QUOTE_ACCOUNT))
+    };
+
+    debug!("OpenBook v2 吃单{}: {} -> {}, 价格lots: {}, base_lots上限: {}, quote_lots上限: {}",
+           if is_bid { "买入" } else { "卖出" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), price_lots, max_base_lots, max_quote_lots);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        // lot数量无法直接换算，等待金库余额变化修正
+        amount_in: 0,
+        amount_out: 0,
+        user_address: ctx.account(SIGNER_ACCOUNT)?,
+        pool_address: ctx.account(MARKET_ACCOUNT)?,
+        // 价格lots限定了最差成交价，数量上限作用于输入一侧
+        amount_specified_is_input: true,
+        other_amount_threshold: 0,
+        user_source,
+        user_destination,
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+
+/// 参数: side, price_lots, max_base_lots, max_quote_lots_including_fees, client_order_id,
+/// order_type, expiry_timestamp, self_trade_behavior, limit
+///
+/// place_order只传入输入一侧的用户代币账户和金库，成交所得先记入open orders账户，
+/// 吃单通常在同一交易中由settle_funds结算，因此输出代币取自签名者持有的另一个代币账户。
+fn decode_place_order(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let mut reader = BorshReader::new(&data[8..]);
+    let is_bid = reader.read_u8()? == SIDE_BID;
+    let price_lots = reader.read_i64()?;
+    let max_base_lots = reader.read_i64()?;
+    let max_quote_lots = reader.read_i64()?;
+    let order_type = reader.skip(8).read_u8()?;
+    if order_type != ORDER_IMMEDIATE_OR_CANCEL && order_type != ORDER_MARKET {
+        debug!("OpenBook v2挂单（订单类型{}），不作为swap处理", order_type);
+        return None;
+    }
+
+    let user_address = ctx.account(SIGNER_ACCOUNT)?;
+    let token_in = ctx
+        .token_mint(PLACE_ORDER_VAULT_ACCOUNT)
+        .or_else(|| ctx.token_mint(PLACE_ORDER_USER_TOKEN_ACCOUNT))?;
+    let Some((user_destination, token_out)) = settlement_account(ctx, &user_address, &token_in) else {
+        debug!("OpenBook v2 place_order: 交易中没有签名者的结算代币账户，无法确定输出代币，跳过");
+        return None;
+    };
+
+    debug!("OpenBook v2 place_order吃单{}: {} -> {}, 价格lots: {}, base_lots上限: {}, quote_lots上限: {}",
+           if is_bid { "买入" } else { "卖出" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), price_lots, max_base_lots, max_quote_lots);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        // lot数量无法直接换算，等待金库余额变化修正
+        amount_in: 0,
+        amount_out: 0,
+        user_address,
+        pool_address: ctx.account(PLACE_ORDER_MARKET_ACCOUNT)?,
+        amount_specified_is_input: true,
+        other_amount_threshold: 0,
+        user_source: ctx.account(PLACE_ORDER_USER_TOKEN_ACCOUNT),
+        user_destination: Some(user_destination),
+        vault_in: ctx.account(PLACE_ORDER_VAULT_ACCOUNT),
+        // 输出一侧的金库不在账户列表中
+        vault_out: None,
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+/// 查找签名者持有的、mint与输入代币不同的代币账户，返回(账户地址, mint)
+fn settlement_account(ctx: &InstructionContext, owner: &str, token_in: &str) -> Option<(String, String)> {
+    ctx.tx
+        .post_token_balances()
+        .iter()
+        .find(|balance| balance.owner.as_deref() == Some(owner) && balance.mint != token_in)
+        .and_then(|balance| {
+            let account = ctx.account_keys.get(balance.account_index)?;
+            Some((account.clone(), balance.mint.clone()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const BASE_MINT: &str = "So11111111111111111111111111111111111111112";
+    const QUOTE_MINT: &str = "EPjFWdd5AufqSSqeM2qJkumSVZzdPcYvFYNMHgQxasLs";
+
+    /// place_take_order参数: side, price_lots, max_base_lots, max_quote_lots_including_fees,
+    /// order_type, limit
+    fn take_order(side: u8) -> Vec<u8> {
+        let mut data = PLACE_TAKE_ORDER.to_vec();
+        data.push(side);
+        data.extend(1_500i64.to_le_bytes());
+        data.extend(10i64.to_le_bytes());
+        data.extend(20_000i64.to_le_bytes());
+        data.push(1);
+        data.push(10);
+        data
+    }
+
+    fn order_transaction(data: &[u8]) -> crate::client::Transaction {
+        let accounts: Vec<String> = (1..=16).map(pubkey).collect();
+        let balances = [
+            (MARKET_BASE_VAULT_ACCOUNT, BASE_MINT, 0, 0),
+            (MARKET_QUOTE_VAULT_ACCOUNT, QUOTE_MINT, 0, 0),
+        ];
+        transaction(OPENBOOK_V2, &accounts, data, &balances)
+    }
+
+    #[test]
+    fn decodes_place_take_order_bid() {
+        let swaps = decode(&OpenBookV2Decoder, &order_transaction(&take_order(SIDE_BID)));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, NAME);
+        assert_eq!(swap.token_in, QUOTE_MINT);
+        assert_eq!(swap.token_out, BASE_MINT);
+        assert_eq!(swap.user_address, pubkey(1));
+        assert_eq!(swap.pool_address, pubkey(3));
+        assert_eq!(swap.user_source, Some(pubkey(11)));
+        assert_eq!(swap.user_destination, Some(pubkey(10)));
+        assert_eq!(swap.vault_in, Some(pubkey(8)));
+        assert_eq!(swap.vault_out, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn decodes_place_take_order_ask() {
+        let swaps = decode(&OpenBookV2Decoder, &order_transaction(&take_order(1)));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.token_in, BASE_MINT);
+        assert_eq!(swap.token_out, QUOTE_MINT);
+        assert_eq!(swap.user_source, Some(pubkey(10)));
+        assert_eq!(swap.vault_in, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn skips_order_management_instructions() {
+        for (_, discriminator) in ORDER_MANAGEMENT_INSTRUCTIONS {
+            let mut data = take_order(SIDE_BID);
+            data[..8].copy_from_slice(&discriminator);
+            assert!(decode(&OpenBookV2Decoder, &order_transaction(&data)).is_empty());
+        }
+    }
+
+    /// place_order参数: side, price_lots, max_base_lots, max_quote_lots_including_fees,
+    /// client_order_id, order_type, expiry_timestamp, self_trade_behavior, limit
+    fn place_order(side: u8, order_type: u8) -> Vec<u8> {
+        let mut data = PLACE_ORDER.to_vec();
+        data.push(side);
+        data.extend(1_500i64.to_le_bytes());
+        data.extend(10i64.to_le_bytes());
+        data.extend(20_000i64.to_le_bytes());
+        data.extend(42u64.to_le_bytes());
+        data.push(order_type);
+        data.extend(0u64.to_le_bytes());
+        data.push(0);
+        data.push(10);
+        data
+    }
+
+    /// 买单：用户quote账户(3)支付到quote金库(8)，同一交易中结算到用户的base账户(12)
+    fn place_order_transaction(data: &[u8], settled: bool) -> crate::client::Transaction {
+        let accounts: Vec<String> = (1..=13).map(pubkey).collect();
+        let mut balances = vec![
+            (PLACE_ORDER_USER_TOKEN_ACCOUNT, QUOTE_MINT, 50_000, 30_000),
+            (PLACE_ORDER_VAULT_ACCOUNT, QUOTE_MINT, 0, 20_000),
+        ];
+        if settled {
+            balances.push((12, BASE_MINT, 0, 10));
+        }
+        let mut tx = transaction(OPENBOOK_V2, &accounts, data, &balances);
+        let meta = tx.meta.as_mut().unwrap();
+        for balance in meta.post_token_balances.iter_mut() {
+            if balance.account_index != PLACE_ORDER_VAULT_ACCOUNT {
+                balance.owner = Some(pubkey(1));
+            }
+        }
+        tx
+    }
+
+    #[test]
+    fn decodes_immediate_place_order() {
+        for order_type in [ORDER_IMMEDIATE_OR_CANCEL, ORDER_MARKET] {
+            let tx = place_order_transaction(&place_order(SIDE_BID, order_type), true);
+            let swaps = decode(&OpenBookV2Decoder, &tx);
+            assert_eq!(swaps.len(), 1);
+            let swap = &swaps[0];
+            assert_eq!(swap.token_in, QUOTE_MINT);
+            assert_eq!(swap.token_out, BASE_MINT);
+            assert_eq!(swap.user_address, pubkey(1));
+            assert_eq!(swap.pool_address, pubkey(5));
+            assert_eq!(swap.user_source, Some(pubkey(4)));
+            assert_eq!(swap.user_destination, Some(pubkey(13)));
+            assert_eq!(swap.vault_in, Some(pubkey(9)));
+            assert_eq!(swap.vault_out, None);
+        }
+    }
+
+    #[test]
+    fn skips_resting_and_unsettled_place_orders() {
+        // Limit, PostOnly, PostOnlySlide
+        for order_type in [0, 2, 4] {
+            let tx = place_order_transaction(&place_order(SIDE_BID, order_type), true);
+            assert!(decode(&OpenBookV2Decoder, &tx).is_empty());
+        }
+        let tx = place_order_transaction(&place_order(SIDE_BID, ORDER_MARKET), false);
+        assert!(decode(&OpenBookV2Decoder, &tx).is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+use super::{infer_token_from_accounts, InstructionContext, SwapDecoder};
+use crate::mev::program_ids::ORCA_V1;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Orca V1";
+
+/// Orca V1（token-swap）解码器
+pub struct OrcaV1Decoder;
+
+impl SwapDecoder for OrcaV1Decoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[ORCA_V1]
+    }
+
+    /// 解析swap指令（标签1）
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    if data.len() < 17 || data[0] != 1 {
+        return None;
+    }
+
+    let amount_in = u64::from_le_bytes(data[1..9].try_into().ok()?);
+    let amount_out = u64::from_le_bytes(data[9..17].try_into().ok()?);
+
+    let user_address = ctx.account(0)?;
+    let pool_address = ctx.account(1)?;
+
+    let token_in = infer_token_from_accounts(&ctx.instruction.accounts, ctx.account_keys, true)?;
+    let token_out = infer_token_from_accounts(&ctx.instruction.accounts, ctx.account_keys, false)?;
+
+    debug!("Orca V1 swap: {} -> {}, amount_in: {}, amount_out: {}",
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address,
+        pool_address,
+        amount_specified_is_input: true,
+        // 没有成交事件可与最少输出对照，不记录滑点边界
+        other_amount_threshold: 0,
+        user_source: None,
+        user_destination: None,
+        vault_in: None,
+        vault_out: None,
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
@@ -0,0 +1,243 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::BorshReader;
+use crate::mev::program_ids::PHOENIX;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Phoenix";
+
+// 指令标签（单字节）
+const SWAP: u8 = 0;
+const SWAP_WITH_FREE_FUNDS: u8 = 1;
+const PLACE_LIMIT_ORDER: u8 = 2;
+const PLACE_LIMIT_ORDER_WITH_FREE_FUNDS: u8 = 3;
+/// 4-11: ReduceOrder / CancelAllOrders / CancelUpTo / CancelMultipleOrdersById 及其WithFreeFunds版本
+const CANCEL_INSTRUCTIONS: std::ops::RangeInclusive<u8> = 4..=11;
+
+// OrderPacket变体
+const ORDER_POST_ONLY: u8 = 0;
+const ORDER_LIMIT: u8 = 1;
+const ORDER_IMMEDIATE_OR_CANCEL: u8 = 2;
+
+const SIDE_BID: u8 = 0;
+
+const MARKET_ACCOUNT: usize = 2;
+const TRADER_ACCOUNT: usize = 3;
+
+/// 使用钱包代币账户结算的指令中，代币账户与金库的位置
+struct TokenAccounts {
+    base_account: usize,
+    quote_account: usize,
+    base_vault: usize,
+    quote_vault: usize,
+}
+
+/// Swap: 0 program, 1 log_authority, 2 market, 3 trader, 4 base_account, 5 quote_account, 6 base_vault, 7 quote_vault
+const SWAP_ACCOUNTS: TokenAccounts = TokenAccounts {
+    base_account: 4,
+    quote_account: 5,
+    base_vault: 6,
+    quote_vault: 7,
+};
+
+/// PlaceLimitOrder: 在trader之后多一个seat账户
+const PLACE_LIMIT_ORDER_ACCOUNTS: TokenAccounts = TokenAccounts {
+    base_account: 5,
+    quote_account: 6,
+    base_vault: 7,
+    quote_vault: 8,
+};
+
+/// Phoenix订单簿解码器
+///
+/// 订单簿的共享可写账户是market（订单簿本身），对应`pool_address`。
+/// 订单数量以lot为单位，换算需要market头部数据，因此实际成交数量由金库余额变化确定。
+pub struct PhoenixDecoder;
+
+impl SwapDecoder for PhoenixDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[PHOENIX]
+    }
+
+    /// 解析Swap和PlaceLimitOrder中的吃单（IOC）订单，挂单和撤单不作为swap处理
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_order(ctx).into_iter().collect()
+    }
+}
+
+fn decode_order(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let tag = *data.first()?;
+    let accounts = match tag {
+        SWAP => &SWAP_ACCOUNTS,
+        PLACE_LIMIT_ORDER => &PLACE_LIMIT_ORDER_ACCOUNTS,
+        SWAP_WITH_FREE_FUNDS | PLACE_LIMIT_ORDER_WITH_FREE_FUNDS => {
+            debug!("Phoenix使用已存入资金的订单（标签{}），没有代币账户变动，跳过", tag);
+            return None;
+        }
+        tag if CANCEL_INSTRUCTIONS.contains(&tag) => {
+            debug!("Phoenix撤单/减单指令（标签{}），不作为swap处理", tag);
+            return None;
+        }
+        _ => return None,
+    };
+
+    let mut reader = BorshReader::new(&data[1..]);
+    let order_type = reader.read_u8()?;
+    let is_bid = reader.read_u8()? == SIDE_BID;
+    match order_type {
+        ORDER_IMMEDIATE_OR_CANCEL => {}
+        ORDER_POST_ONLY | ORDER_LIMIT => {
+            debug!("Phoenix挂单（{}），不作为swap处理", if is_bid { "买" } else { "卖" });
+            return None;
+        }
+        _ => return None,
+    }
+
+    // IOC: side, price_in_ticks: Option<u64>, num_base_lots, num_quote_lots,
+    // min_base_lots_to_fill, min_quote_lots_to_fill, ...
+    if reader.read_bool()? {
+        reader.skip(8);
+    }
+    let num_base_lots = reader.read_u64()?;
+    let num_quote_lots = reader.read_u64()?;
+
+    let base_mint = ctx
+        .token_mint(accounts.base_vault)
+        .or_else(|| ctx.token_mint(accounts.base_account))?;
+    let quote_mint = ctx
+        .token_mint(accounts.quote_vault)
+        .or_else(|| ctx.token_mint(accounts.quote_account))?;
+    let base_vault = ctx.account(accounts.base_vault)?;
+    let quote_vault = ctx.account(accounts.quote_vault)?;
+
+    // 买单支付quote得到base，卖单相反
+    let (token_in, token_out, vault_in, vault_out, user_source, user_destination) = if is_bid {
+        (quote_mint, base_mint, quote_vault, base_vault,
+         ctx.account(accounts.quote_account), ctx.account(accounts.base_account))
+    } else {
+        (base_mint, quote_mint, base_vault, quote_vault,
+         ctx.account(accounts.base_account), ctx.account(accounts.quote_account))
+    };
+
+    // 卖单按base数量下单；买单指定quote数量时为exact-in，否则按base数量（exact-out）
+    let amount_specified_is_input = !is_bid || num_quote_lots > 0;
+
+    debug!("Phoenix IOC {}单: {} -> {}, base_lots: {}, quote_lots: {}",
+           if is_bid { "买" } else { "卖" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), num_base_lots, num_quote_lots);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        // lot数量无法直接换算，等待金库余额变化修正
+        amount_in: 0,
+        amount_out: 0,
+        user_address: ctx.account(TRADER_ACCOUNT)?,
+        pool_address: ctx.account(MARKET_ACCOUNT)?,
+        amount_specified_is_input,
+        other_amount_threshold: 0,
+        user_source,
+        user_destination,
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const BASE_MINT: &str = "So11111111111111111111111111111111111111112";
+    const QUOTE_MINT: &str = "EPjFWdd5AufqSSqeM2qJkumSVZzdPcYvFYNMHgQxasLs";
+
+    /// OrderPacket::ImmediateOrCancel: side, price_in_ticks, num_base_lots, num_quote_lots,
+    /// min_base_lots_to_fill, min_quote_lots_to_fill, self_trade_behavior, match_limit,
+    /// client_order_id, use_only_deposited_funds
+    fn ioc_order(tag: u8, side: u8, num_base_lots: u64, num_quote_lots: u64) -> Vec<u8> {
+        let mut data = vec![tag, ORDER_IMMEDIATE_OR_CANCEL, side];
+        data.push(1);
+        data.extend(1_500u64.to_le_bytes());
+        data.extend(num_base_lots.to_le_bytes());
+        data.extend(num_quote_lots.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        data.push(0);
+        data.push(0);
+        data.extend(7u128.to_le_bytes());
+        data.push(0);
+        data
+    }
+
+    fn order_transaction(data: &[u8], accounts: &TokenAccounts) -> crate::client::Transaction {
+        let keys: Vec<String> = (1..=9).map(pubkey).collect();
+        let balances = [
+            (accounts.base_vault, BASE_MINT, 0, 0),
+            (accounts.quote_vault, QUOTE_MINT, 0, 0),
+        ];
+        transaction(PHOENIX, &keys, data, &balances)
+    }
+
+    #[test]
+    fn decodes_ioc_swap() {
+        let tx = order_transaction(&ioc_order(SWAP, SIDE_BID, 0, 250_000), &SWAP_ACCOUNTS);
+        let swaps = decode(&PhoenixDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, NAME);
+        assert_eq!(swap.token_in, QUOTE_MINT);
+        assert_eq!(swap.token_out, BASE_MINT);
+        assert!(swap.amount_specified_is_input);
+        assert_eq!((swap.amount_in, swap.amount_out), (0, 0));
+        assert_eq!(swap.pool_address, pubkey(3));
+        assert_eq!(swap.user_address, pubkey(4));
+        assert_eq!(swap.user_source, Some(pubkey(6)));
+        assert_eq!(swap.user_destination, Some(pubkey(5)));
+        assert_eq!(swap.vault_in, Some(pubkey(8)));
+        assert_eq!(swap.vault_out, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn decodes_ioc_limit_order_sell() {
+        let tx = order_transaction(&ioc_order(PLACE_LIMIT_ORDER, 1, 40, 0), &PLACE_LIMIT_ORDER_ACCOUNTS);
+        let swaps = decode(&PhoenixDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.token_in, BASE_MINT);
+        assert_eq!(swap.token_out, QUOTE_MINT);
+        assert!(swap.amount_specified_is_input);
+        assert_eq!(swap.user_source, Some(pubkey(6)));
+        assert_eq!(swap.user_destination, Some(pubkey(7)));
+        assert_eq!(swap.vault_in, Some(pubkey(8)));
+        assert_eq!(swap.vault_out, Some(pubkey(9)));
+    }
+
+    #[test]
+    fn skips_resting_orders_free_funds_and_cancels() {
+        let mut post_only = ioc_order(PLACE_LIMIT_ORDER, SIDE_BID, 1, 0);
+        post_only[1] = ORDER_POST_ONLY;
+        let mut limit = ioc_order(PLACE_LIMIT_ORDER, SIDE_BID, 1, 0);
+        limit[1] = ORDER_LIMIT;
+        for data in [
+            post_only,
+            limit,
+            ioc_order(SWAP_WITH_FREE_FUNDS, SIDE_BID, 1, 0),
+            ioc_order(PLACE_LIMIT_ORDER_WITH_FREE_FUNDS, SIDE_BID, 1, 0),
+            ioc_order(6, SIDE_BID, 1, 0),
+        ] {
+            let tx = order_transaction(&data, &PLACE_LIMIT_ORDER_ACCOUNTS);
+            assert!(decode(&PhoenixDecoder, &tx).is_empty());
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::PUMP_FUN;
+use crate::mev::token_info::{get_token_symbol, WSOL};
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Pump.fun";
+
+const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// sha256("event:TradeEvent")的前8字节
+const TRADE_EVENT: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+const MINT_ACCOUNT: usize = 2;
+const BONDING_CURVE_ACCOUNT: usize = 3;
+const ASSOCIATED_BONDING_CURVE_ACCOUNT: usize = 4;
+const ASSOCIATED_USER_ACCOUNT: usize = 5;
+const USER_ACCOUNT: usize = 6;
+
+/// Pump.fun `TradeEvent`
+#[derive(Debug, Clone)]
+pub struct PumpFunTradeEvent {
+    pub mint: String,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: String,
+    /// 交易后的虚拟SOL储备
+    pub virtual_sol_reserves: u64,
+    /// 交易后的虚拟代币储备
+    pub virtual_token_reserves: u64,
+}
+
+/// Pump.fun联合曲线状态（虚拟储备）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BondingCurveState {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+}
+
+impl BondingCurveState {
+    /// 按恒定乘积计算投入`sol_in`可买到的代币数量（不含手续费）
+    pub fn quote_buy(&self, sol_in: u64) -> u64 {
+        let k = self.virtual_sol_reserves as u128 * self.virtual_token_reserves as u128;
+        let new_sol = self.virtual_sol_reserves as u128 + sol_in as u128;
+        if new_sol == 0 {
+            return 0;
+        }
+        let new_token = k.div_ceil(new_sol);
+        (self.virtual_token_reserves as u128).saturating_sub(new_token) as u64
+    }
+
+    /// 按恒定乘积计算卖出`token_in`可获得的SOL数量（不含手续费）
+    pub fn quote_sell(&self, token_in: u64) -> u64 {
+        let k = self.virtual_sol_reserves as u128 * self.virtual_token_reserves as u128;
+        let new_token = self.virtual_token_reserves as u128 + token_in as u128;
+        if new_token == 0 {
+            return 0;
+        }
+        let new_sol = k.div_ceil(new_token);
+        (self.virtual_sol_reserves as u128).saturating_sub(new_sol) as u64
+    }
+}
+
+impl PumpFunTradeEvent {
+    /// 交易后的曲线状态（事件中直接给出）
+    pub fn curve_after(&self) -> BondingCurveState {
+        BondingCurveState {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+        }
+    }
+
+    /// 交易前的曲线状态（由交易后状态和成交数量反推）
+    pub fn curve_before(&self) -> BondingCurveState {
+        if self.is_buy {
+            BondingCurveState {
+                virtual_sol_reserves: self.virtual_sol_reserves.saturating_sub(self.sol_amount),
+                virtual_token_reserves: self.virtual_token_reserves.saturating_add(self.token_amount),
+            }
+        } else {
+            BondingCurveState {
+                virtual_sol_reserves: self.virtual_sol_reserves.saturating_add(self.sol_amount),
+                virtual_token_reserves: self.virtual_token_reserves.saturating_sub(self.token_amount),
+            }
+        }
+    }
+}
+
+/// Pump.fun联合曲线解码器
+pub struct PumpFunDecoder;
+
+impl SwapDecoder for PumpFunDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[PUMP_FUN]
+    }
+
+    /// 解析buy/sell指令
+    ///
+    /// 指令数据为8字节Anchor鉴别器 + 两个u64参数：
+    /// - buy: `amount`（买入的代币数量）, `max_sol_cost`（最多支付的SOL）
+    /// - sell: `amount`（卖出的代币数量）, `min_sol_output`（最少获得的SOL）
+    ///
+    /// 账户: 0 global, 1 fee_recipient, 2 mint, 3 bonding_curve,
+    /// 4 associated_bonding_curve, 5 associated_user, 6 user, ...
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_trade(ctx).into_iter().collect()
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&TRADE_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        let trade = PumpFunTradeEvent {
+            mint: reader.read_pubkey()?,
+            sol_amount: reader.read_u64()?,
+            token_amount: reader.read_u64()?,
+            is_buy: reader.read_bool()?,
+            user: reader.read_pubkey()?,
+            // 跳过timestamp
+            virtual_sol_reserves: reader.skip(8).read_u64()?,
+            virtual_token_reserves: reader.read_u64()?,
+        };
+        debug!("解码到Pump.fun事件: {:?}", trade);
+        let (amount_in, amount_out) = if trade.is_buy {
+            (trade.sol_amount, trade.token_amount)
+        } else {
+            (trade.token_amount, trade.sol_amount)
+        };
+        Some(SwapEvent::new(amount_in, amount_out).with_venue_data(trade))
+    }
+
+    /// TradeEvent不包含联合曲线地址，按代币mint匹配
+    fn event_matches(&self, event: &SwapEvent, swap: &SwapInstructionData) -> bool {
+        event
+            .venue_data::<PumpFunTradeEvent>()
+            .is_some_and(|e| e.mint == swap.token_in || e.mint == swap.token_out)
+    }
+
+    /// TradeEvent明确给出了代币mint、方向和用户
+    fn apply_event(&self, swap: &mut SwapInstructionData, event: &SwapEvent) {
+        let Some(e) = event.venue_data::<PumpFunTradeEvent>() else {
+            return;
+        };
+        if e.is_buy {
+            swap.token_in = WSOL.to_string();
+            swap.token_out = e.mint.clone();
+        } else {
+            swap.token_in = e.mint.clone();
+            swap.token_out = WSOL.to_string();
+        }
+        swap.user_address = e.user.clone();
+        debug!("Pump.fun TradeEvent: 曲线状态 {:?} -> {:?}", e.curve_before(), e.curve_after());
+    }
+}
+
+fn decode_trade(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let discriminator: [u8; 8] = data.get(0..8)?.try_into().ok()?;
+    let is_buy = match discriminator {
+        BUY => true,
+        SELL => false,
+        _ => return None,
+    };
+
+    let amount = u64::from_le_bytes(data.get(8..16)?.try_into().ok()?);
+    let sol_limit = u64::from_le_bytes(data.get(16..24)?.try_into().ok()?);
+
+    let mint = ctx.account(MINT_ACCOUNT)?;
+    let pool_address = ctx.account(BONDING_CURVE_ACCOUNT)?;
+    let curve_token_account = ctx.account(ASSOCIATED_BONDING_CURVE_ACCOUNT);
+    let user_token_account = ctx.account(ASSOCIATED_USER_ACCOUNT);
+    let user_address = ctx.account(USER_ACCOUNT)?;
+
+    // 在TradeEvent修正之前，SOL一侧只能先使用用户的滑点边界
+    // SOL直接存放在bonding_curve账户中，代币存放在其关联代币账户中
+    let (token_in, token_out, amount_in, amount_out) = if is_buy {
+        (WSOL.to_string(), mint, sol_limit, amount)
+    } else {
+        (mint, WSOL.to_string(), amount, sol_limit)
+    };
+    let (user_source, user_destination, vault_in, vault_out) = if is_buy {
+        (None, user_token_account, Some(pool_address.clone()), curve_token_account)
+    } else {
+        (user_token_account, None, curve_token_account, Some(pool_address.clone()))
+    };
+
+    debug!("Pump.fun {}: {} -> {}, amount: {}, sol限制: {}",
+           if is_buy { "buy" } else { "sell" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount, sol_limit);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address,
+        pool_address,
+        // buy指定输出的代币数量，sell指定输入的代币数量
+        amount_specified_is_input: !is_buy,
+        other_amount_threshold: sol_limit,
+        user_source,
+        user_destination,
+        vault_in,
+        vault_out,
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    fn trade_instruction(discriminator: [u8; 8], amount: u64, sol_limit: u64) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(amount.to_le_bytes());
+        data.extend(sol_limit.to_le_bytes());
+        data
+    }
+
+    /// 0 global, 1 fee_recipient, 2 mint, 3 bonding_curve, 4 associated_bonding_curve,
+    /// 5 associated_user, 6 user, ...
+    fn trade_transaction(data: &[u8]) -> crate::client::Transaction {
+        let accounts: Vec<String> = (1..=12).map(pubkey).collect();
+        transaction(PUMP_FUN, &accounts, data, &[])
+    }
+
+    /// 带有TradeEvent日志的buy/sell交易（代币mint为`pubkey(3)`）
+    pub(crate) fn trade_with_event(is_buy: bool, sol_amount: u64, token_amount: u64) -> crate::client::Transaction {
+        use base64::engine::general_purpose::STANDARD as BASE64;
+        use base64::Engine;
+
+        let data = if is_buy {
+            trade_instruction(BUY, token_amount, sol_amount)
+        } else {
+            trade_instruction(SELL, token_amount, sol_amount)
+        };
+        let mut tx = trade_transaction(&data);
+        tx.meta.as_mut().unwrap().log_messages = vec![
+            format!("Program {} invoke [1]", PUMP_FUN),
+            format!("Program data: {}", BASE64.encode(trade_event(is_buy, sol_amount, token_amount))),
+            format!("Program {} success", PUMP_FUN),
+        ];
+        tx
+    }
+
+    /// TradeEvent: mint, sol_amount, token_amount, is_buy, user, timestamp,
+    /// virtual_sol_reserves, virtual_token_reserves
+    pub(crate) fn trade_event(is_buy: bool, sol_amount: u64, token_amount: u64) -> Vec<u8> {
+        let mut data = TRADE_EVENT.to_vec();
+        data.extend([3u8; 32]);
+        data.extend(sol_amount.to_le_bytes());
+        data.extend(token_amount.to_le_bytes());
+        data.push(is_buy as u8);
+        data.extend([7u8; 32]);
+        data.extend(1_700_000_000i64.to_le_bytes());
+        data.extend(31_000_000_000u64.to_le_bytes());
+        data.extend(1_038_000_000_000_000u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_trade_event() {
+        let event = PumpFunDecoder.decode_event(&trade_event(true, 500_000_000, 16_000_000_000_000)).unwrap();
+        assert_eq!((event.amount_in, event.amount_out), (500_000_000, 16_000_000_000_000));
+        assert_eq!(event.pool, None);
+
+        let trade = event.venue_data::<PumpFunTradeEvent>().unwrap();
+        assert_eq!(trade.mint, pubkey(3));
+        assert_eq!(trade.sol_amount, 500_000_000);
+        assert_eq!(trade.token_amount, 16_000_000_000_000);
+        assert!(trade.is_buy);
+        assert_eq!(trade.user, pubkey(7));
+        assert_eq!(trade.virtual_sol_reserves, 31_000_000_000);
+        assert_eq!(trade.virtual_token_reserves, 1_038_000_000_000_000);
+    }
+
+    #[test]
+    fn decodes_sell_trade_event_amounts() {
+        let event = PumpFunDecoder.decode_event(&trade_event(false, 400_000_000, 15_000_000_000_000)).unwrap();
+        assert_eq!((event.amount_in, event.amount_out), (15_000_000_000_000, 400_000_000));
+        assert!(!event.venue_data::<PumpFunTradeEvent>().unwrap().is_buy);
+    }
+
+    #[test]
+    fn ignores_other_events() {
+        let mut data = trade_event(true, 1, 1);
+        data[0] ^= 0xff;
+        assert!(PumpFunDecoder.decode_event(&data).is_none());
+        assert!(PumpFunDecoder.decode_event(&TRADE_EVENT).is_none());
+    }
+
+    #[test]
+    fn decodes_buy_instruction() {
+        let tx = trade_transaction(&trade_instruction(BUY, 16_000_000_000_000, 550_000_000));
+        let swaps = decode(&PumpFunDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, NAME);
+        assert_eq!(swap.token_in, WSOL);
+        assert_eq!(swap.token_out, pubkey(3));
+        assert_eq!(swap.amount_in, 550_000_000);
+        assert_eq!(swap.amount_out, 16_000_000_000_000);
+        assert!(!swap.amount_specified_is_input);
+        assert_eq!(swap.other_amount_threshold, 550_000_000);
+        assert_eq!(swap.pool_address, pubkey(4));
+        assert_eq!(swap.user_address, pubkey(7));
+        assert_eq!(swap.user_source, None);
+        assert_eq!(swap.user_destination, Some(pubkey(6)));
+        assert_eq!(swap.vault_in, Some(pubkey(4)));
+        assert_eq!(swap.vault_out, Some(pubkey(5)));
+    }
+
+    #[test]
+    fn decodes_sell_instruction() {
+        let tx = trade_transaction(&trade_instruction(SELL, 15_000_000_000_000, 390_000_000));
+        let swaps = decode(&PumpFunDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.token_in, pubkey(3));
+        assert_eq!(swap.token_out, WSOL);
+        assert_eq!(swap.amount_in, 15_000_000_000_000);
+        assert_eq!(swap.amount_out, 390_000_000);
+        assert!(swap.amount_specified_is_input);
+        assert_eq!(swap.user_source, Some(pubkey(6)));
+        assert_eq!(swap.user_destination, None);
+        assert_eq!(swap.vault_in, Some(pubkey(5)));
+        assert_eq!(swap.vault_out, Some(pubkey(4)));
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        // create指令
+        let create = [24, 30, 200, 40, 5, 28, 7, 119];
+        let tx = trade_transaction(&trade_instruction(create, 1, 2));
+        assert!(decode(&PumpFunDecoder, &tx).is_empty());
+        // 参数不完整
+        let tx = trade_transaction(&BUY);
+        assert!(decode(&PumpFunDecoder, &tx).is_empty());
+    }
+
+    #[test]
+    fn quotes_constant_product() {
+        let curve = BondingCurveState {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+        };
+        // 1_073_000_000_000_000 - ceil(k / 31_000_000_000)
+        assert_eq!(curve.quote_buy(1_000_000_000), 34_612_903_225_806);
+        // 30_000_000_000 - ceil(k / 1_083_000_000_000_000)
+        assert_eq!(curve.quote_sell(10_000_000_000_000), 277_008_310);
+        assert_eq!(curve.quote_buy(0), 0);
+        assert_eq!(curve.quote_sell(0), 0);
+
+        let empty = BondingCurveState { virtual_sol_reserves: 0, virtual_token_reserves: 0 };
+        assert_eq!(empty.quote_buy(0), 0);
+        assert_eq!(empty.quote_sell(0), 0);
+    }
+
+    #[test]
+    fn reconstructs_curve_before_trade() {
+        let event = PumpFunDecoder.decode_event(&trade_event(true, 500_000_000, 16_000_000_000_000)).unwrap();
+        let trade = event.venue_data::<PumpFunTradeEvent>().unwrap();
+        let before = trade.curve_before();
+        assert_eq!(before.virtual_sol_reserves, 30_500_000_000);
+        assert_eq!(before.virtual_token_reserves, 1_054_000_000_000_000);
+        assert_eq!(trade.curve_after().virtual_sol_reserves, 31_000_000_000);
+
+        let event = PumpFunDecoder.decode_event(&trade_event(false, 400_000_000, 15_000_000_000_000)).unwrap();
+        let before = event.venue_data::<PumpFunTradeEvent>().unwrap().curve_before();
+        assert_eq!(before.virtual_sol_reserves, 31_400_000_000);
+        assert_eq!(before.virtual_token_reserves, 1_023_000_000_000_000);
+    }
+}
@@ -0,0 +1,318 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::RAYDIUM_AMM;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Raydium AMM";
+
+const SWAP_BASE_IN: u8 = 9;
+const SWAP_BASE_OUT: u8 = 11;
+
+/// Raydium AMM通过`ray_log`输出的日志前缀
+const RAY_LOG_PREFIX: &str = "ray_log: ";
+
+/// swap指令中各角色账户的位置
+struct AccountLayout {
+    amm: usize,
+    coin_vault: usize,
+    pc_vault: usize,
+    user_source: usize,
+    user_destination: usize,
+    user_owner: usize,
+}
+
+/// 18个账户的版本（包含amm_target_orders）
+const LAYOUT_18: AccountLayout = AccountLayout {
+    amm: 1,
+    coin_vault: 5,
+    pc_vault: 6,
+    user_source: 15,
+    user_destination: 16,
+    user_owner: 17,
+};
+
+/// 17个账户的版本（不含amm_target_orders）
+const LAYOUT_17: AccountLayout = AccountLayout {
+    amm: 1,
+    coin_vault: 4,
+    pc_vault: 5,
+    user_source: 14,
+    user_destination: 15,
+    user_owner: 16,
+};
+
+/// Raydium AMM v4 `ray_log`中的swap记录（日志不包含池子地址）
+#[derive(Debug, Clone)]
+pub struct RaydiumSwapLog {
+    /// true为swapBaseIn，false为swapBaseOut
+    pub base_in: bool,
+    /// swapBaseIn: amount_in；swapBaseOut: max_amount_in
+    pub amount_in_limit: u64,
+    /// swapBaseIn: minimum_amount_out；swapBaseOut: amount_out
+    pub amount_out_limit: u64,
+    /// 1 = PC -> Coin，2 = Coin -> PC
+    pub direction: u64,
+    /// swap前池子的coin储备
+    pub pool_coin: u64,
+    /// swap前池子的pc储备
+    pub pool_pc: u64,
+    /// swapBaseIn: 实际输出；swapBaseOut: 实际扣除的输入
+    pub settled_amount: u64,
+}
+
+impl RaydiumSwapLog {
+    /// 实际成交的（输入数量，输出数量）
+    pub fn settled_amounts(&self) -> (u64, u64) {
+        if self.base_in {
+            (self.amount_in_limit, self.settled_amount)
+        } else {
+            (self.settled_amount, self.amount_out_limit)
+        }
+    }
+}
+
+/// Raydium AMM v4解码器
+pub struct RaydiumAmmDecoder;
+
+impl SwapDecoder for RaydiumAmmDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[RAYDIUM_AMM]
+    }
+
+    /// 解析swapBaseIn / swapBaseOut指令
+    ///
+    /// - swapBaseIn (9): `amount_in`, `minimum_amount_out`
+    /// - swapBaseOut (11): `max_amount_in`, `amount_out`
+    ///
+    /// 账户布局有18个（含amm_target_orders）和17个两种版本，
+    /// 代币mint和交易方向通过pre/postTokenBalances解析。
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+
+    fn decode_log_message(&self, message: &str) -> Option<SwapEvent> {
+        let encoded = message.strip_prefix(RAY_LOG_PREFIX)?;
+        let data = BASE64.decode(encoded.trim()).ok()?;
+        let log = decode_ray_log(&data)?;
+        let (amount_in, amount_out) = log.settled_amounts();
+        Some(SwapEvent::new(amount_in, amount_out).with_venue_data(log))
+    }
+
+    fn apply_event(&self, _swap: &mut SwapInstructionData, event: &SwapEvent) {
+        if let Some(log) = event.venue_data::<RaydiumSwapLog>() {
+            debug!("ray_log: 方向={}, 池子储备 coin={} pc={}",
+                   log.direction, log.pool_coin, log.pool_pc);
+        }
+    }
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let base_in = match *data.first()? {
+        SWAP_BASE_IN => true,
+        SWAP_BASE_OUT => false,
+        _ => return None,
+    };
+    // swapBaseIn: amount_in, minimum_amount_out；swapBaseOut: max_amount_in, amount_out
+    let amount_in = u64::from_le_bytes(data.get(1..9)?.try_into().ok()?);
+    let amount_out = u64::from_le_bytes(data.get(9..17)?.try_into().ok()?);
+    let other_amount_threshold = if base_in { amount_out } else { amount_in };
+
+    let layout = match ctx.instruction.accounts.len() {
+        len if len >= 18 => &LAYOUT_18,
+        17 => &LAYOUT_17,
+        len => {
+            debug!("Raydium AMM swap账户数量异常: {}", len);
+            return None;
+        }
+    };
+
+    let coin_mint = ctx.token_mint(layout.coin_vault)?;
+    let pc_mint = ctx.token_mint(layout.pc_vault)?;
+
+    // 交易方向：优先看用户源账户的mint，其次看金库余额变化（输入金库余额增加）
+    let source_mint = ctx.token_mint(layout.user_source);
+    let coin_to_pc = if source_mint.as_ref() == Some(&coin_mint) {
+        true
+    } else if source_mint.as_ref() == Some(&pc_mint) {
+        false
+    } else {
+        match ctx.token_amount_change(layout.coin_vault) {
+            Some(change) => change > 0,
+            None => {
+                debug!("无法确定Raydium AMM swap方向，默认coin -> pc");
+                true
+            }
+        }
+    };
+
+    let coin_vault = ctx.account(layout.coin_vault)?;
+    let pc_vault = ctx.account(layout.pc_vault)?;
+    let (token_in, token_out, vault_in, vault_out) = if coin_to_pc {
+        (coin_mint, pc_mint, coin_vault, pc_vault)
+    } else {
+        (pc_mint, coin_mint, pc_vault, coin_vault)
+    };
+
+    // 未被指定的一侧此时是滑点边界，之后由ray_log或余额变化修正
+    debug!("Raydium AMM {}: {} -> {}, in: {}, out: {}",
+           if base_in { "swapBaseIn" } else { "swapBaseOut" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address: ctx.account(layout.user_owner)?,
+        pool_address: ctx.account(layout.amm)?,
+        amount_specified_is_input: base_in,
+        other_amount_threshold,
+        user_source: ctx.account(layout.user_source),
+        user_destination: ctx.account(layout.user_destination),
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+/// 解码Raydium AMM的`ray_log`（仅swap类型）
+fn decode_ray_log(data: &[u8]) -> Option<RaydiumSwapLog> {
+    let mut reader = BorshReader::new(data);
+    let base_in = match reader.read_u8()? {
+        3 => true,
+        4 => false,
+        // 0: Init, 1: Deposit, 2: Withdraw
+        _ => return None,
+    };
+
+    Some(RaydiumSwapLog {
+        base_in,
+        amount_in_limit: reader.read_u64()?,
+        amount_out_limit: reader.read_u64()?,
+        direction: reader.read_u64()?,
+        // 用户源账户余额，检测中不需要
+        pool_coin: reader.skip(8).read_u64()?,
+        pool_pc: reader.read_u64()?,
+        settled_amount: reader.read_u64()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const COIN_MINT: &str = "So11111111111111111111111111111111111111112";
+    const PC_MINT: &str = "EPjFWdd5AufqSSqeM2qJkumSVZzdPcYvFYNMHgQxasLs";
+
+    /// 18个账户的swap交易：用户源账户持有coin（coin -> pc）
+    fn swap_transaction(data: &[u8]) -> crate::client::Transaction {
+        let accounts: Vec<String> = (1..=18).map(pubkey).collect();
+        let balances = [
+            (LAYOUT_18.coin_vault, COIN_MINT, 1_000, 1_000),
+            (LAYOUT_18.pc_vault, PC_MINT, 2_000, 2_000),
+            (LAYOUT_18.user_source, COIN_MINT, 500, 500),
+        ];
+        transaction(RAYDIUM_AMM, &accounts, data, &balances)
+    }
+
+    fn instruction(tag: u8, first: u64, second: u64) -> Vec<u8> {
+        let mut data = vec![tag];
+        data.extend(first.to_le_bytes());
+        data.extend(second.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_swap_base_in() {
+        let tx = swap_transaction(&instruction(SWAP_BASE_IN, 1_000_000, 950_000));
+        let swaps = decode(&RaydiumAmmDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert!(swap.amount_specified_is_input);
+        assert_eq!(swap.amount_in, 1_000_000);
+        assert_eq!(swap.amount_out, 950_000);
+        assert_eq!(swap.other_amount_threshold, 950_000);
+        assert_eq!(swap.token_in, COIN_MINT);
+        assert_eq!(swap.token_out, PC_MINT);
+        assert_eq!(swap.pool_address, pubkey(2));
+        assert_eq!(swap.user_address, pubkey(18));
+        assert_eq!(swap.vault_in, Some(pubkey(6)));
+        assert_eq!(swap.vault_out, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn decodes_swap_base_out() {
+        // max_amount_in, amount_out
+        let tx = swap_transaction(&instruction(SWAP_BASE_OUT, 1_050_000, 1_000_000));
+        let swaps = decode(&RaydiumAmmDecoder, &tx);
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert!(!swap.amount_specified_is_input);
+        assert_eq!(swap.amount_in, 1_050_000);
+        assert_eq!(swap.amount_out, 1_000_000);
+        assert_eq!(swap.other_amount_threshold, 1_050_000);
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        // 3: Deposit
+        let tx = swap_transaction(&instruction(3, 1, 2));
+        assert!(decode(&RaydiumAmmDecoder, &tx).is_empty());
+    }
+
+    /// `ray_log`: log_type, 两个指令参数, direction, user_source, pool_coin, pool_pc, 成交数量
+    fn ray_log(log_type: u8, fields: [u64; 7]) -> String {
+        let mut data = vec![log_type];
+        for field in fields {
+            data.extend(field.to_le_bytes());
+        }
+        format!("{}{}", RAY_LOG_PREFIX, BASE64.encode(data))
+    }
+
+    #[test]
+    fn decodes_swap_base_in_log() {
+        let message = ray_log(3, [1_000_000, 900_000, 2, 5_000_000, 80_000_000, 160_000_000, 1_950_000]);
+        let event = RaydiumAmmDecoder.decode_log_message(&message).expect("应解码ray_log");
+        assert_eq!((event.amount_in, event.amount_out), (1_000_000, 1_950_000));
+        assert_eq!(event.pool, None);
+
+        let log = event.venue_data::<RaydiumSwapLog>().unwrap();
+        assert!(log.base_in);
+        assert_eq!(log.amount_in_limit, 1_000_000);
+        assert_eq!(log.amount_out_limit, 900_000);
+        assert_eq!(log.direction, 2);
+        assert_eq!(log.pool_coin, 80_000_000);
+        assert_eq!(log.pool_pc, 160_000_000);
+        assert_eq!(log.settled_amount, 1_950_000);
+    }
+
+    #[test]
+    fn decodes_swap_base_out_log() {
+        // max_amount_in, amount_out, ..., 实际扣除的输入
+        let message = ray_log(4, [1_100_000, 2_000_000, 1, 5_000_000, 80_000_000, 160_000_000, 1_020_000]);
+        let event = RaydiumAmmDecoder.decode_log_message(&message).expect("应解码ray_log");
+        assert_eq!((event.amount_in, event.amount_out), (1_020_000, 2_000_000));
+        assert!(!event.venue_data::<RaydiumSwapLog>().unwrap().base_in);
+    }
+
+    #[test]
+    fn ignores_non_swap_logs() {
+        // 1: Deposit
+        assert!(RaydiumAmmDecoder.decode_log_message(&ray_log(1, [0; 7])).is_none());
+        assert!(RaydiumAmmDecoder.decode_log_message("Instruction: SwapBaseIn").is_none());
+    }
+}
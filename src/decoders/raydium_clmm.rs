@@ -0,0 +1,265 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::RAYDIUM_CLMM;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Raydium CLMM";
+
+const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+const SWAP_V2: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// sha256("event:SwapEvent")的前8字节
+const SWAP_EVENT: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+
+/// 仓位与流动性管理指令，与swap区分开，不参与MEV分析
+const LIQUIDITY_INSTRUCTIONS: [(&str, [u8; 8]); 10] = [
+    ("create_pool", [233, 146, 209, 142, 207, 104, 64, 188]),
+    ("open_position", [135, 128, 47, 77, 15, 152, 240, 49]),
+    ("open_position_v2", [77, 184, 74, 214, 112, 86, 241, 199]),
+    ("open_position_with_token22_nft", [77, 255, 174, 82, 125, 29, 201, 46]),
+    ("increase_liquidity", [46, 156, 243, 118, 13, 205, 251, 178]),
+    ("increase_liquidity_v2", [133, 29, 89, 223, 69, 238, 176, 10]),
+    ("decrease_liquidity", [160, 38, 208, 111, 104, 91, 44, 1]),
+    ("decrease_liquidity_v2", [58, 127, 188, 62, 79, 82, 196, 96]),
+    ("close_position", [123, 134, 81, 0, 49, 68, 98, 98]),
+    ("collect_remaining_rewards", [18, 237, 166, 197, 34, 16, 213, 144]),
+];
+
+// swap / swap_v2共有的账户位置
+const PAYER_ACCOUNT: usize = 0;
+const POOL_STATE_ACCOUNT: usize = 2;
+const INPUT_TOKEN_ACCOUNT: usize = 3;
+const OUTPUT_TOKEN_ACCOUNT: usize = 4;
+const INPUT_VAULT_ACCOUNT: usize = 5;
+const OUTPUT_VAULT_ACCOUNT: usize = 6;
+// 仅swap_v2包含的mint账户
+const INPUT_VAULT_MINT_ACCOUNT: usize = 11;
+const OUTPUT_VAULT_MINT_ACCOUNT: usize = 12;
+
+/// Raydium CLMM解码器
+pub struct RaydiumClmmDecoder;
+
+impl SwapDecoder for RaydiumClmmDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[RAYDIUM_CLMM]
+    }
+
+    /// 按Anchor鉴别器解析swap / swap_v2指令
+    ///
+    /// 参数：amount, other_amount_threshold, sqrt_price_limit_x64, is_base_input。
+    /// 仓位和流动性管理指令会被识别出来并跳过，不作为swap处理。
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&SWAP_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        // 布局: pool_state, sender, token_account_0, token_account_1,
+        // amount_0, transfer_fee_0, amount_1, transfer_fee_1, zero_for_one, ...
+        let pool_state = reader.read_pubkey()?;
+        let amount_0 = reader.skip(32 * 3).read_u64()?;
+        let amount_1 = reader.skip(8).read_u64()?;
+        let zero_for_one = reader.skip(8).read_bool()?;
+        let (amount_in, amount_out) = if zero_for_one {
+            (amount_0, amount_1)
+        } else {
+            (amount_1, amount_0)
+        };
+        let event = SwapEvent::new(amount_in, amount_out).with_pool(pool_state);
+        debug!("解码到Raydium CLMM事件: {:?}", event);
+        Some(event)
+    }
+}
+
+/// 返回流动性管理指令的名称
+fn liquidity_instruction_name(discriminator: &[u8]) -> Option<&'static str> {
+    LIQUIDITY_INSTRUCTIONS
+        .iter()
+        .find(|(_, d)| d.as_slice() == discriminator)
+        .map(|(name, _)| *name)
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let discriminator = data.get(..8)?;
+
+    let is_v2 = if discriminator == SWAP {
+        false
+    } else if discriminator == SWAP_V2 {
+        true
+    } else {
+        match liquidity_instruction_name(discriminator) {
+            Some(name) => debug!("Raydium CLMM流动性操作: {}，不作为swap处理", name),
+            None => debug!("未识别的Raydium CLMM指令: {:?}", discriminator),
+        }
+        return None;
+    };
+
+    let mut reader = BorshReader::new(&data[8..]);
+    let amount = reader.read_u64()?;
+    let other_amount_threshold = reader.read_u64()?;
+    let sqrt_price_limit = reader.read_u128()?;
+    let is_base_input = reader.read_bool()?;
+
+    // swap_v2直接传入金库mint；swap需要通过代币余额解析
+    let mint_at = |mint_position: usize, token_accounts: [usize; 2]| -> Option<String> {
+        if is_v2 {
+            return ctx.account(mint_position);
+        }
+        token_accounts
+            .iter()
+            .find_map(|&position| ctx.token_mint(position))
+    };
+
+    let token_in = mint_at(INPUT_VAULT_MINT_ACCOUNT, [INPUT_VAULT_ACCOUNT, INPUT_TOKEN_ACCOUNT])?;
+    let token_out = mint_at(OUTPUT_VAULT_MINT_ACCOUNT, [OUTPUT_VAULT_ACCOUNT, OUTPUT_TOKEN_ACCOUNT])?;
+
+    // 未被指定的一侧先以滑点边界占位，之后由SwapEvent或余额变化修正
+    let (amount_in, amount_out) = if is_base_input {
+        (amount, other_amount_threshold)
+    } else {
+        (other_amount_threshold, amount)
+    };
+
+    debug!("Raydium CLMM {}: {} -> {}, amount: {}, exact_in: {}, 滑点边界: {}, sqrt价格限制: {}",
+           if is_v2 { "swap_v2" } else { "swap" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out),
+           amount, is_base_input, other_amount_threshold, sqrt_price_limit);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address: ctx.account(PAYER_ACCOUNT)?,
+        pool_address: ctx.account(POOL_STATE_ACCOUNT)?,
+        amount_specified_is_input: is_base_input,
+        other_amount_threshold,
+        user_source: ctx.account(INPUT_TOKEN_ACCOUNT),
+        user_destination: ctx.account(OUTPUT_TOKEN_ACCOUNT),
+        vault_in: ctx.account(INPUT_VAULT_ACCOUNT),
+        vault_out: ctx.account(OUTPUT_VAULT_ACCOUNT),
+        sqrt_price_limit: Some(sqrt_price_limit),
+        event: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const MINT_IN: &str = "So11111111111111111111111111111111111111112";
+    const MINT_OUT: &str = "EPjFWdd5AufqSSqeM2qJkumSVZzdPcYvFYNMHgQxasLs";
+
+    /// 参数：amount, other_amount_threshold, sqrt_price_limit_x64, is_base_input
+    fn swap_args(discriminator: [u8; 8], amount: u64, threshold: u64, is_base_input: bool) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(amount.to_le_bytes());
+        data.extend(threshold.to_le_bytes());
+        data.extend(79_226_673_521_066_979_257_578_248_091u128.to_le_bytes());
+        data.push(is_base_input as u8);
+        data
+    }
+
+    /// SwapEvent: pool_state, sender, token_account_0, token_account_1, amount_0, transfer_fee_0,
+    /// amount_1, transfer_fee_1, zero_for_one, sqrt_price_x64, liquidity, tick
+    fn swap_event(zero_for_one: bool, amount_0: u64, amount_1: u64) -> Vec<u8> {
+        let mut data = SWAP_EVENT.to_vec();
+        for n in 1..=4 {
+            data.extend([n; 32]);
+        }
+        data.extend(amount_0.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        data.extend(amount_1.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        data.push(zero_for_one as u8);
+        data.extend((1u128 << 64).to_le_bytes());
+        data.extend(5_000_000u128.to_le_bytes());
+        data.extend((-120i32).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_swap_event() {
+        let event = RaydiumClmmDecoder.decode_event(&swap_event(true, 1_000, 2_500)).unwrap();
+        assert_eq!(event.pool.as_deref(), Some(pubkey(1).as_str()));
+        assert_eq!((event.amount_in, event.amount_out), (1_000, 2_500));
+
+        // one_for_zero: 输入为token_1
+        let event = RaydiumClmmDecoder.decode_event(&swap_event(false, 1_000, 2_500)).unwrap();
+        assert_eq!((event.amount_in, event.amount_out), (2_500, 1_000));
+    }
+
+    #[test]
+    fn decodes_swap() {
+        let accounts: Vec<String> = (1..=10).map(pubkey).collect();
+        let balances = [
+            (INPUT_VAULT_ACCOUNT, MINT_IN, 0, 0),
+            (OUTPUT_VAULT_ACCOUNT, MINT_OUT, 0, 0),
+        ];
+        let data = swap_args(SWAP, 1_000_000, 140_000, true);
+        let swaps = decode(&RaydiumClmmDecoder, &transaction(RAYDIUM_CLMM, &accounts, &data, &balances));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, NAME);
+        assert_eq!(swap.token_in, MINT_IN);
+        assert_eq!(swap.token_out, MINT_OUT);
+        assert_eq!(swap.amount_in, 1_000_000);
+        assert_eq!(swap.amount_out, 140_000);
+        assert!(swap.amount_specified_is_input);
+        assert_eq!(swap.other_amount_threshold, 140_000);
+        assert_eq!(swap.sqrt_price_limit, Some(79_226_673_521_066_979_257_578_248_091));
+        assert_eq!(swap.user_address, pubkey(1));
+        assert_eq!(swap.pool_address, pubkey(3));
+        assert_eq!(swap.user_source, Some(pubkey(4)));
+        assert_eq!(swap.user_destination, Some(pubkey(5)));
+        assert_eq!(swap.vault_in, Some(pubkey(6)));
+        assert_eq!(swap.vault_out, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn decodes_exact_out_swap_v2() {
+        let mut accounts: Vec<String> = (1..=13).map(pubkey).collect();
+        accounts[INPUT_VAULT_MINT_ACCOUNT] = MINT_IN.to_string();
+        accounts[OUTPUT_VAULT_MINT_ACCOUNT] = MINT_OUT.to_string();
+        let data = swap_args(SWAP_V2, 150_000, 1_100_000, false);
+        let swaps = decode(&RaydiumClmmDecoder, &transaction(RAYDIUM_CLMM, &accounts, &data, &[]));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.token_in, MINT_IN);
+        assert_eq!(swap.token_out, MINT_OUT);
+        assert_eq!(swap.amount_in, 1_100_000);
+        assert_eq!(swap.amount_out, 150_000);
+        assert!(!swap.amount_specified_is_input);
+        assert_eq!(swap.other_amount_threshold, 1_100_000);
+    }
+
+    #[test]
+    fn skips_liquidity_instructions() {
+        let accounts: Vec<String> = (1..=10).map(pubkey).collect();
+        let balances = [
+            (INPUT_VAULT_ACCOUNT, MINT_IN, 0, 0),
+            (OUTPUT_VAULT_ACCOUNT, MINT_OUT, 0, 0),
+        ];
+        let decode_with = |discriminator: [u8; 8]| {
+            let data = swap_args(discriminator, 1, 2, true);
+            decode(&RaydiumClmmDecoder, &transaction(RAYDIUM_CLMM, &accounts, &data, &balances))
+        };
+        assert_eq!(decode_with(SWAP).len(), 1);
+        for (_, discriminator) in LIQUIDITY_INSTRUCTIONS {
+            assert!(decode_with(discriminator).is_empty());
+        }
+    }
+}
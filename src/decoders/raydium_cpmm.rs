@@ -0,0 +1,152 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::RAYDIUM_CPMM;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Raydium CPMM";
+
+const SWAP_BASE_INPUT: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+const SWAP_BASE_OUTPUT: [u8; 8] = [55, 217, 98, 86, 163, 74, 180, 173];
+
+/// sha256("event:SwapEvent")的前8字节
+const SWAP_EVENT: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+
+const PAYER_ACCOUNT: usize = 0;
+const POOL_STATE_ACCOUNT: usize = 3;
+const INPUT_TOKEN_ACCOUNT: usize = 4;
+const OUTPUT_TOKEN_ACCOUNT: usize = 5;
+const INPUT_VAULT_ACCOUNT: usize = 6;
+const OUTPUT_VAULT_ACCOUNT: usize = 7;
+const INPUT_TOKEN_MINT_ACCOUNT: usize = 10;
+const OUTPUT_TOKEN_MINT_ACCOUNT: usize = 11;
+
+/// Raydium CP-Swap（CPMM）解码器
+pub struct RaydiumCpmmDecoder;
+
+impl SwapDecoder for RaydiumCpmmDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[RAYDIUM_CPMM]
+    }
+
+    /// 解析swap_base_input / swap_base_output指令
+    ///
+    /// - swap_base_input: `amount_in`, `minimum_amount_out`
+    /// - swap_base_output: `max_amount_in`, `amount_out`
+    ///
+    /// 指令直接给出输入/输出的账户、金库和mint，方向无需推断。
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        decode_swap(ctx).into_iter().collect()
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&SWAP_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        // 布局: pool_id, input_vault_before, output_vault_before,
+        // input_amount, output_amount, input_transfer_fee, output_transfer_fee, base_input, ...
+        let pool_id = reader.read_pubkey()?;
+        let input_amount = reader.skip(8 * 2).read_u64()?;
+        let output_amount = reader.read_u64()?;
+        let event = SwapEvent::new(input_amount, output_amount).with_pool(pool_id);
+        debug!("解码到Raydium CPMM事件: {:?}", event);
+        Some(event)
+    }
+}
+
+fn decode_swap(ctx: &InstructionContext) -> Option<SwapInstructionData> {
+    let data = ctx.data()?;
+    let discriminator = data.get(..8)?;
+    let base_input = if discriminator == SWAP_BASE_INPUT {
+        true
+    } else if discriminator == SWAP_BASE_OUTPUT {
+        false
+    } else {
+        return None;
+    };
+
+    // 未被指定的一侧此时是滑点边界，之后由SwapEvent或余额变化修正
+    let mut reader = BorshReader::new(&data[8..]);
+    let (amount_in, amount_out, other_amount_threshold) = if base_input {
+        let amount_in = reader.read_u64()?;
+        let minimum_amount_out = reader.read_u64()?;
+        (amount_in, minimum_amount_out, minimum_amount_out)
+    } else {
+        let max_amount_in = reader.read_u64()?;
+        let amount_out = reader.read_u64()?;
+        (max_amount_in, amount_out, max_amount_in)
+    };
+
+    let token_in = ctx.account(INPUT_TOKEN_MINT_ACCOUNT)?;
+    let token_out = ctx.account(OUTPUT_TOKEN_MINT_ACCOUNT)?;
+
+    debug!("Raydium CPMM {}: {} -> {}, in: {}, out: {}",
+           if base_input { "swap_base_input" } else { "swap_base_output" },
+           get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in,
+        amount_out,
+        user_address: ctx.account(PAYER_ACCOUNT)?,
+        pool_address: ctx.account(POOL_STATE_ACCOUNT)?,
+        amount_specified_is_input: base_input,
+        other_amount_threshold,
+        user_source: ctx.account(INPUT_TOKEN_ACCOUNT),
+        user_destination: ctx.account(OUTPUT_TOKEN_ACCOUNT),
+        vault_in: ctx.account(INPUT_VAULT_ACCOUNT),
+        vault_out: ctx.account(OUTPUT_VAULT_ACCOUNT),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    fn swap_transaction(discriminator: [u8; 8], first: u64, second: u64) -> crate::client::Transaction {
+        let mut data = discriminator.to_vec();
+        data.extend(first.to_le_bytes());
+        data.extend(second.to_le_bytes());
+        let accounts: Vec<String> = (1..=13).map(pubkey).collect();
+        transaction(RAYDIUM_CPMM, &accounts, &data, &[])
+    }
+
+    #[test]
+    fn decodes_swap_base_input() {
+        let swaps = decode(&RaydiumCpmmDecoder, &swap_transaction(SWAP_BASE_INPUT, 2_000, 1_900));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert!(swap.amount_specified_is_input);
+        assert_eq!((swap.amount_in, swap.amount_out), (2_000, 1_900));
+        assert_eq!(swap.other_amount_threshold, 1_900);
+        assert_eq!(swap.user_address, pubkey(1));
+        assert_eq!(swap.pool_address, pubkey(4));
+        assert_eq!(swap.token_in, pubkey(11));
+        assert_eq!(swap.token_out, pubkey(12));
+        assert_eq!(swap.vault_in, Some(pubkey(7)));
+        assert_eq!(swap.vault_out, Some(pubkey(8)));
+    }
+
+    #[test]
+    fn decodes_swap_base_output() {
+        // max_amount_in, amount_out
+        let swaps = decode(&RaydiumCpmmDecoder, &swap_transaction(SWAP_BASE_OUTPUT, 2_100, 2_000));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert!(!swap.amount_specified_is_input);
+        assert_eq!((swap.amount_in, swap.amount_out), (2_100, 2_000));
+        assert_eq!(swap.other_amount_threshold, 2_100);
+    }
+}
@@ -0,0 +1,394 @@
+use super::{InstructionContext, SwapDecoder};
+use crate::logs::{BorshReader, SwapEvent};
+use crate::mev::program_ids::ORCA_WHIRLPOOLS;
+use crate::mev::token_info::get_token_symbol;
+use crate::mev::SwapInstructionData;
+use log::debug;
+
+/// 解码器名称，同时作为swap的场所名称
+const NAME: &str = "Orca Whirlpool";
+
+const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+const SWAP_V2: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+const TWO_HOP_SWAP: [u8; 8] = [195, 96, 237, 108, 68, 162, 219, 230];
+
+/// sha256("event:Traded")的前8字节
+const TRADED_EVENT: [u8; 8] = [225, 202, 73, 175, 147, 43, 160, 150];
+
+/// 单个whirlpool上一次swap涉及的账户位置
+struct LegLayout {
+    token_authority: usize,
+    whirlpool: usize,
+    owner_account_a: usize,
+    vault_a: usize,
+    owner_account_b: usize,
+    vault_b: usize,
+    /// swapV2直接传入mint账户；swap需要通过金库的代币余额解析
+    mints: Option<(usize, usize)>,
+}
+
+const SWAP_LAYOUT: LegLayout = LegLayout {
+    token_authority: 1,
+    whirlpool: 2,
+    owner_account_a: 3,
+    vault_a: 4,
+    owner_account_b: 5,
+    vault_b: 6,
+    mints: None,
+};
+
+const SWAP_V2_LAYOUT: LegLayout = LegLayout {
+    token_authority: 3,
+    whirlpool: 4,
+    owner_account_a: 7,
+    vault_a: 8,
+    owner_account_b: 9,
+    vault_b: 10,
+    mints: Some((5, 6)),
+};
+
+const TWO_HOP_LEG_ONE: LegLayout = LegLayout {
+    token_authority: 1,
+    whirlpool: 2,
+    owner_account_a: 4,
+    vault_a: 5,
+    owner_account_b: 6,
+    vault_b: 7,
+    mints: None,
+};
+
+const TWO_HOP_LEG_TWO: LegLayout = LegLayout {
+    token_authority: 1,
+    whirlpool: 3,
+    owner_account_a: 8,
+    vault_a: 9,
+    owner_account_b: 10,
+    vault_b: 11,
+    mints: None,
+};
+
+/// Orca Whirlpool `Traded`事件中成交数量以外的字段
+#[derive(Debug, Clone)]
+pub struct WhirlpoolTraded {
+    pub a_to_b: bool,
+}
+
+/// Orca Whirlpool解码器
+pub struct WhirlpoolDecoder;
+
+impl SwapDecoder for WhirlpoolDecoder {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn program_ids(&self) -> &[&str] {
+        &[ORCA_WHIRLPOOLS]
+    }
+
+    /// 解析swap / swapV2 / twoHopSwap指令
+    ///
+    /// 参数中的`amount`由`amount_specified_is_input`决定是输入还是输出数量，
+    /// `other_amount_threshold`是另一侧的滑点边界，方向由`a_to_b`决定。
+    fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+        let Some(data) = ctx.data() else {
+            return Vec::new();
+        };
+        let Some(discriminator) = data.get(..8) else {
+            return Vec::new();
+        };
+
+        let swaps = if discriminator == SWAP {
+            decode_single_swap(ctx, &data[8..], &SWAP_LAYOUT).into_iter().collect()
+        } else if discriminator == SWAP_V2 {
+            decode_single_swap(ctx, &data[8..], &SWAP_V2_LAYOUT).into_iter().collect()
+        } else if discriminator == TWO_HOP_SWAP {
+            decode_two_hop_swap(ctx, &data[8..])
+                .map(Vec::from)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        for swap in &swaps {
+            debug!("Orca Whirlpool swap: {} -> {}, amount_in: {}, amount_out: {}, exact_in: {}, 滑点边界: {}",
+                   get_token_symbol(&swap.token_in), get_token_symbol(&swap.token_out),
+                   swap.amount_in, swap.amount_out, swap.amount_specified_is_input, swap.other_amount_threshold);
+        }
+        swaps
+    }
+
+    fn decode_event(&self, data: &[u8]) -> Option<SwapEvent> {
+        let payload = data.strip_prefix(&TRADED_EVENT)?;
+        let mut reader = BorshReader::new(payload);
+
+        // 布局: whirlpool, a_to_b, pre_sqrt_price, post_sqrt_price,
+        // input_amount, output_amount, 各类手续费...
+        let whirlpool = reader.read_pubkey()?;
+        let a_to_b = reader.read_bool()?;
+        let input_amount = reader.skip(16 * 2).read_u64()?;
+        let output_amount = reader.read_u64()?;
+        let event = SwapEvent::new(input_amount, output_amount)
+            .with_pool(whirlpool)
+            .with_venue_data(WhirlpoolTraded { a_to_b });
+        debug!("解码到Orca Whirlpool事件: {:?}", event);
+        Some(event)
+    }
+
+    fn apply_event(&self, swap: &mut SwapInstructionData, event: &SwapEvent) {
+        if let Some(e) = event.venue_data::<WhirlpoolTraded>() {
+            debug!("Whirlpool Traded: a_to_b={}, {} -> {}",
+                   e.a_to_b, get_token_symbol(&swap.token_in), get_token_symbol(&swap.token_out));
+        }
+    }
+}
+
+/// 解析swap / swapV2的参数：amount, other_amount_threshold, sqrt_price_limit,
+/// amount_specified_is_input, a_to_b
+fn decode_single_swap(
+    ctx: &InstructionContext,
+    args: &[u8],
+    layout: &LegLayout,
+) -> Option<SwapInstructionData> {
+    let mut reader = BorshReader::new(args);
+    let amount = reader.read_u64()?;
+    let other_amount_threshold = reader.read_u64()?;
+    let sqrt_price_limit = reader.read_u128()?;
+    let amount_specified_is_input = reader.read_bool()?;
+    let a_to_b = reader.read_bool()?;
+
+    let mut swap = decode_leg(ctx, layout, a_to_b)?;
+    // 未被指定的一侧先以滑点边界占位，之后由Traded事件或余额变化修正
+    if amount_specified_is_input {
+        swap.amount_in = amount;
+        swap.amount_out = other_amount_threshold;
+    } else {
+        swap.amount_in = other_amount_threshold;
+        swap.amount_out = amount;
+    }
+    swap.amount_specified_is_input = amount_specified_is_input;
+    swap.other_amount_threshold = other_amount_threshold;
+    swap.sqrt_price_limit = Some(sqrt_price_limit);
+    Some(swap)
+}
+
+/// 解析twoHopSwap，拆分为两个whirlpool上的两次swap
+///
+/// 参数：amount, other_amount_threshold, amount_specified_is_input, a_to_b_one, a_to_b_two,
+/// sqrt_price_limit_one, sqrt_price_limit_two。滑点边界作用于整条路径，
+/// exact-in时约束第二跳的输出，exact-out时约束第一跳的输入。
+fn decode_two_hop_swap(ctx: &InstructionContext, args: &[u8]) -> Option<[SwapInstructionData; 2]> {
+    let mut reader = BorshReader::new(args);
+    let amount = reader.read_u64()?;
+    let other_amount_threshold = reader.read_u64()?;
+    let amount_specified_is_input = reader.read_bool()?;
+    let a_to_b_one = reader.read_bool()?;
+    let a_to_b_two = reader.read_bool()?;
+    let sqrt_price_limit_one = reader.read_u128()?;
+    let sqrt_price_limit_two = reader.read_u128()?;
+
+    let mut leg_one = decode_leg(ctx, &TWO_HOP_LEG_ONE, a_to_b_one)?;
+    let mut leg_two = decode_leg(ctx, &TWO_HOP_LEG_TWO, a_to_b_two)?;
+
+    // 中间代币数量在指令中不可知，保持为0等待事件或余额变化修正
+    if amount_specified_is_input {
+        leg_one.amount_in = amount;
+        leg_two.amount_out = other_amount_threshold;
+        leg_two.other_amount_threshold = other_amount_threshold;
+    } else {
+        leg_one.amount_in = other_amount_threshold;
+        leg_one.other_amount_threshold = other_amount_threshold;
+        leg_two.amount_out = amount;
+    }
+    leg_one.amount_specified_is_input = amount_specified_is_input;
+    leg_two.amount_specified_is_input = amount_specified_is_input;
+    leg_one.sqrt_price_limit = Some(sqrt_price_limit_one);
+    leg_two.sqrt_price_limit = Some(sqrt_price_limit_two);
+
+    Some([leg_one, leg_two])
+}
+
+/// 按账户布局解析一个whirlpool上的swap方向、代币、金库和用户账户（数量由调用方填写）
+fn decode_leg(ctx: &InstructionContext, layout: &LegLayout, a_to_b: bool) -> Option<SwapInstructionData> {
+    let (mint_a, mint_b) = match layout.mints {
+        Some((mint_a, mint_b)) => (ctx.account(mint_a)?, ctx.account(mint_b)?),
+        None => (ctx.token_mint(layout.vault_a)?, ctx.token_mint(layout.vault_b)?),
+    };
+    let vault_a = ctx.account(layout.vault_a)?;
+    let vault_b = ctx.account(layout.vault_b)?;
+    let owner_account_a = ctx.account(layout.owner_account_a);
+    let owner_account_b = ctx.account(layout.owner_account_b);
+
+    let (token_in, token_out, vault_in, vault_out, user_source, user_destination) = if a_to_b {
+        (mint_a, mint_b, vault_a, vault_b, owner_account_a, owner_account_b)
+    } else {
+        (mint_b, mint_a, vault_b, vault_a, owner_account_b, owner_account_a)
+    };
+
+    Some(SwapInstructionData {
+        venue: NAME,
+        program_id: ctx.program_id.to_string(),
+        token_in,
+        token_out,
+        amount_in: 0,
+        amount_out: 0,
+        user_address: ctx.account(layout.token_authority)?,
+        pool_address: ctx.account(layout.whirlpool)?,
+        amount_specified_is_input: true,
+        other_amount_threshold: 0,
+        user_source,
+        user_destination,
+        vault_in: Some(vault_in),
+        vault_out: Some(vault_out),
+        sqrt_price_limit: None,
+        event: None,
+    })
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::decoders::test_support::{decode, pubkey, transaction};
+
+    const MINT_A: &str = "So11111111111111111111111111111111111111112";
+    const MINT_B: &str = "EPjFWdd5AufqSSqeM2qJkumSVZzdPcYvFYNMHgQxasLs";
+    const MINT_C: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    /// swap / swapV2参数：amount, other_amount_threshold, sqrt_price_limit,
+    /// amount_specified_is_input, a_to_b
+    fn swap_args(discriminator: [u8; 8], amount: u64, threshold: u64, exact_in: bool, a_to_b: bool) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(amount.to_le_bytes());
+        data.extend(threshold.to_le_bytes());
+        data.extend(4_295_048_016u128.to_le_bytes());
+        data.push(exact_in as u8);
+        data.push(a_to_b as u8);
+        data
+    }
+
+    /// Traded: whirlpool, a_to_b, pre_sqrt_price, post_sqrt_price, input_amount, output_amount,
+    /// input_transfer_fee, output_transfer_fee, lp_fee, protocol_fee
+    pub(crate) fn traded_event(a_to_b: bool, input_amount: u64, output_amount: u64) -> Vec<u8> {
+        let mut data = TRADED_EVENT.to_vec();
+        data.extend([1u8; 32]);
+        data.push(a_to_b as u8);
+        data.extend((3u128 << 64).to_le_bytes());
+        data.extend((2u128 << 64).to_le_bytes());
+        data.extend(input_amount.to_le_bytes());
+        data.extend(output_amount.to_le_bytes());
+        for fee in [0u64, 0, 30, 3] {
+            data.extend(fee.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_traded_event() {
+        let event = WhirlpoolDecoder.decode_event(&traded_event(true, 7_000, 6_900)).unwrap();
+        assert_eq!(event.pool.as_deref(), Some(pubkey(1).as_str()));
+        assert_eq!((event.amount_in, event.amount_out), (7_000, 6_900));
+        assert!(event.venue_data::<WhirlpoolTraded>().unwrap().a_to_b);
+    }
+
+    #[test]
+    fn decodes_swap() {
+        let accounts: Vec<String> = (1..=11).map(pubkey).collect();
+        let balances = [
+            (SWAP_LAYOUT.vault_a, MINT_A, 0, 0),
+            (SWAP_LAYOUT.vault_b, MINT_B, 0, 0),
+        ];
+        let data = swap_args(SWAP, 1_000_000, 145_000, true, true);
+        let swaps = decode(&WhirlpoolDecoder, &transaction(ORCA_WHIRLPOOLS, &accounts, &data, &balances));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, NAME);
+        assert_eq!(swap.token_in, MINT_A);
+        assert_eq!(swap.token_out, MINT_B);
+        assert_eq!(swap.amount_in, 1_000_000);
+        assert_eq!(swap.amount_out, 145_000);
+        assert!(swap.amount_specified_is_input);
+        assert_eq!(swap.other_amount_threshold, 145_000);
+        assert_eq!(swap.sqrt_price_limit, Some(4_295_048_016));
+        assert_eq!(swap.user_address, pubkey(2));
+        assert_eq!(swap.pool_address, pubkey(3));
+        assert_eq!(swap.user_source, Some(pubkey(4)));
+        assert_eq!(swap.user_destination, Some(pubkey(6)));
+        assert_eq!(swap.vault_in, Some(pubkey(5)));
+        assert_eq!(swap.vault_out, Some(pubkey(7)));
+    }
+
+    #[test]
+    fn decodes_exact_out_swap_v2() {
+        let mut accounts: Vec<String> = (1..=15).map(pubkey).collect();
+        accounts[5] = MINT_A.to_string();
+        accounts[6] = MINT_B.to_string();
+        // b -> a，指定输出数量
+        let data = swap_args(SWAP_V2, 2_000_000, 300_000, false, false);
+        let swaps = decode(&WhirlpoolDecoder, &transaction(ORCA_WHIRLPOOLS, &accounts, &data, &[]));
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.token_in, MINT_B);
+        assert_eq!(swap.token_out, MINT_A);
+        assert_eq!(swap.amount_in, 300_000);
+        assert_eq!(swap.amount_out, 2_000_000);
+        assert!(!swap.amount_specified_is_input);
+        assert_eq!(swap.other_amount_threshold, 300_000);
+        assert_eq!(swap.user_address, pubkey(4));
+        assert_eq!(swap.pool_address, pubkey(5));
+        assert_eq!(swap.user_source, Some(pubkey(10)));
+        assert_eq!(swap.user_destination, Some(pubkey(8)));
+        assert_eq!(swap.vault_in, Some(pubkey(11)));
+        assert_eq!(swap.vault_out, Some(pubkey(9)));
+    }
+
+    #[test]
+    fn decodes_two_hop_swap_into_two_legs() {
+        let accounts: Vec<String> = (1..=16).map(pubkey).collect();
+        let balances = [
+            (TWO_HOP_LEG_ONE.vault_a, MINT_A, 0, 0),
+            (TWO_HOP_LEG_ONE.vault_b, MINT_B, 0, 0),
+            (TWO_HOP_LEG_TWO.vault_a, MINT_C, 0, 0),
+            (TWO_HOP_LEG_TWO.vault_b, MINT_B, 0, 0),
+        ];
+        // amount, other_amount_threshold, amount_specified_is_input, a_to_b_one, a_to_b_two,
+        // sqrt_price_limit_one, sqrt_price_limit_two
+        let mut data = TWO_HOP_SWAP.to_vec();
+        data.extend(1_000_000u64.to_le_bytes());
+        data.extend(900_000_000u64.to_le_bytes());
+        data.extend([1, 1, 0]);
+        data.extend(11u128.to_le_bytes());
+        data.extend(22u128.to_le_bytes());
+
+        let swaps = decode(&WhirlpoolDecoder, &transaction(ORCA_WHIRLPOOLS, &accounts, &data, &balances));
+        assert_eq!(swaps.len(), 2);
+        let (one, two) = (&swaps[0], &swaps[1]);
+
+        // 第一跳: a -> b
+        assert_eq!((one.token_in.as_str(), one.token_out.as_str()), (MINT_A, MINT_B));
+        assert_eq!(one.pool_address, pubkey(3));
+        assert_eq!((one.amount_in, one.amount_out), (1_000_000, 0));
+        assert_eq!(one.sqrt_price_limit, Some(11));
+        assert_eq!(one.vault_in, Some(pubkey(6)));
+        assert_eq!(one.vault_out, Some(pubkey(8)));
+
+        // 第二跳: b -> c，滑点边界约束最终输出
+        assert_eq!((two.token_in.as_str(), two.token_out.as_str()), (MINT_B, MINT_C));
+        assert_eq!(two.pool_address, pubkey(4));
+        assert_eq!((two.amount_in, two.amount_out), (0, 900_000_000));
+        assert_eq!(two.other_amount_threshold, 900_000_000);
+        assert_eq!(two.sqrt_price_limit, Some(22));
+        assert_eq!(two.vault_in, Some(pubkey(12)));
+        assert_eq!(two.vault_out, Some(pubkey(10)));
+
+        assert!(one.amount_specified_is_input && two.amount_specified_is_input);
+        assert_eq!(one.user_address, pubkey(2));
+        assert_eq!(two.user_address, pubkey(2));
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        let accounts: Vec<String> = (1..=11).map(pubkey).collect();
+        // increaseLiquidity
+        let data = swap_args([46, 156, 243, 118, 13, 205, 251, 178], 1, 2, true, true);
+        assert!(decode(&WhirlpoolDecoder, &transaction(ORCA_WHIRLPOOLS, &accounts, &data, &[])).is_empty());
+    }
+}
@@ -143,6 +143,13 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
+    pub fn get_block_failed(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Failed to get block:",
+            Language::Chinese => "获取区块失败:",
+        }
+    }
+
     pub fn get_nearby_failed(&self) -> &'static str {
         match self.lang {
             Language::English => "Failed to get nearby transactions: {}",
@@ -157,73 +164,101 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
-    pub fn analyzing_nearby(&self) -> &'static str {
+    pub fn pruned_history(&self) -> &'static str {
         match self.lang {
-            Language::English => "📊 Retrieved {} nearby transactions, starting analysis...",
-            Language::Chinese => "📊 获取到周围{}笔交易，开始分析...",
+            Language::English => "🗄️ The RPC node has pruned this part of the ledger history. Use an archive node in config.toml to analyze older transactions.",
+            Language::Chinese => "🗄️ RPC节点已裁剪这部分账本历史，分析较早的交易请在config.toml中配置归档节点",
         }
     }
 
-    pub fn jito_bundle_detected(&self) -> &'static str {
+    pub fn tx_not_found(&self) -> &'static str {
         match self.lang {
-            Language::English => "🎯 Jito bundle detected, analyzing for MEV attack...",
-            Language::Chinese => "🎯 检测到Jito捆绑包交易，正在分析MEV攻击...",
+            Language::English => "❓ Transaction not found. Check the signature, or the node may no longer keep this transaction.",
+            Language::Chinese => "❓ 未找到该交易，请检查交易哈希，或节点已不再保存该交易",
         }
     }
 
-    pub fn tip_location(&self) -> &'static str {
+    pub fn offline_mode(&self) -> &'static str {
         match self.lang {
-            Language::English => "📍 Jito tip location: {} the target transaction",
-            Language::Chinese => "📍 Jito小费位置: 目标交易{}",
+            Language::English => "📴 Offline mode: answering only from the disk cache, no RPC requests will be sent.",
+            Language::Chinese => "📴 离线模式：只使用磁盘缓存中的数据，不发送RPC请求",
         }
     }
 
-    pub fn tip_location_before(&self) -> &'static str {
+    pub fn offline_cache_miss(&self) -> &'static str {
         match self.lang {
-            Language::English => "before",
-            Language::Chinese => "前方",
+            Language::English => "📴 Required data is not in the disk cache. Run once without --offline to fill the cache.",
+            Language::Chinese => "📴 磁盘缓存中没有所需的数据，请先在非离线模式下运行一次以填充缓存",
         }
     }
 
-    pub fn tip_location_after(&self) -> &'static str {
+    pub fn jito_api_offline(&self) -> &'static str {
         match self.lang {
-            Language::English => "after",
-            Language::Chinese => "后方",
+            Language::English => "📴 Offline mode: skipping the Jito bundle API, using the traditional analysis method",
+            Language::Chinese => "📴 离线模式：不查询Jito束包API，使用传统分析方法",
         }
     }
 
-    pub fn tip_amount(&self) -> &'static str {
+    pub fn target_tx_failed(&self) -> &'static str {
         match self.lang {
-            Language::English => "💰 Tip amount:",
-            Language::Chinese => "💰 小费金额:",
+            Language::English => "❌ The target transaction failed on-chain and did not trade, so it cannot have been sandwiched. Error:",
+            Language::Chinese => "❌ 目标交易执行失败，没有实际成交，不存在三明治损失。错误:",
         }
     }
 
-    pub fn bundle_contains(&self) -> &'static str {
+    pub fn target_block_position(&self) -> &'static str {
         match self.lang {
-            Language::English => "📦 Bundle contains {} transactions:",
-            Language::Chinese => "📦 捆绑包包含{}笔交易:",
+            Language::English => "📍 Target transaction position (slot#index): {}",
+            Language::Chinese => "📍 目标交易位置 (slot#区块内索引): {}",
         }
     }
 
-    pub fn jito_tip_tx(&self) -> &'static str {
+    pub fn failed_attempts_found(&self) -> &'static str {
         match self.lang {
-            Language::English => ". Jito tip transaction ⭐",
-            Language::Chinese => ". Jito小费交易 ⭐",
+            Language::English => "🚫 Failed attack attempts on the same pool (not counted as sandwiches): {}",
+            Language::Chinese => "🚫 同一池子上执行失败的攻击尝试（不计入三明治攻击）: {}",
         }
     }
 
-    pub fn target_tx(&self) -> &'static str {
+    pub fn failed_attempt_before(&self) -> &'static str {
         match self.lang {
-            Language::English => ". Target transaction 🎯",
-            Language::Chinese => ". 目标交易 🎯",
+            Language::English => "before target",
+            Language::Chinese => "目标交易之前",
         }
     }
 
-    pub fn other_tx(&self) -> &'static str {
+    pub fn failed_attempt_after(&self) -> &'static str {
         match self.lang {
-            Language::English => ". Other transaction",
-            Language::Chinese => ". 其他交易",
+            Language::English => "after target",
+            Language::Chinese => "目标交易之后",
+        }
+    }
+
+    pub fn awaiting_finalization(&self) -> &'static str {
+        match self.lang {
+            Language::English => "⏳ Possible sandwich found, but not all involved transactions are finalized yet (finalized_only is on). Try again in a few seconds.",
+            Language::Chinese => "⏳ 发现疑似三明治攻击，但涉及的交易尚未全部finalized（已开启finalized_only），请稍后重试",
+        }
+    }
+
+    pub fn finality_check_failed(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Failed to verify transaction finality:",
+            Language::Chinese => "无法确认交易是否已finalized:",
+        }
+    }
+
+    pub fn analyzing_nearby(&self) -> &'static str {
+        match self.lang {
+            Language::English => "📊 Retrieved {} nearby transactions, starting analysis...",
+            Language::Chinese => "📊 获取到周围{}笔交易，开始分析...",
+        }
+    }
+
+    pub fn jito_bundle_detected(&self) -> &'static str {
+        match self.lang {
+            Language::English => "🎯 Jito bundle detected, analyzing for MEV attack...",
+            Language::Chinese => "🎯 检测到Jito捆绑包交易，正在分析MEV攻击...",
         }
     }
 
@@ -243,6 +278,265 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
+    pub fn victim_tx(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Victim transaction: https://solscan.io/tx/",
+            Language::Chinese => "  受害交易: https://solscan.io/tx/",
+        }
+    }
+
+    pub fn attacker(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Attacker:",
+            Language::Chinese => "  攻击者:",
+        }
+    }
+
+    pub fn attacked_pools(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Pools:",
+            Language::Chinese => "  池子:",
+        }
+    }
+
+    pub fn block_scan_start(&self) -> &'static str {
+        match self.lang {
+            Language::English => "🔍 Scanning every transaction in block {} for sandwich attacks...",
+            Language::Chinese => "🔍 扫描区块 {} 中的所有交易...",
+        }
+    }
+
+    pub fn block_scan_result(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Sandwich attacks found in block:",
+            Language::Chinese => "区块中发现的三明治攻击数量:",
+        }
+    }
+
+    pub fn invalid_slot(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Invalid slot, expected a number:",
+            Language::Chinese => "无效的slot，应为数字:",
+        }
+    }
+
+    pub fn invalid_slot_range(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Invalid slot range, expected <start>-<end>:",
+            Language::Chinese => "无效的slot范围，应为 <起始slot>-<结束slot>:",
+        }
+    }
+
+    pub fn scan_resumed(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Resuming scan from checkpoint at slot",
+            Language::Chinese => "从检查点继续扫描，起始slot",
+        }
+    }
+
+    pub fn checkpoint_range_mismatch(&self) -> &'static str {
+        match self.lang {
+            Language::English => "The checkpoint file belongs to a different slot range; delete it or pass another --checkpoint",
+            Language::Chinese => "检查点文件属于其他slot范围，请删除或通过--checkpoint指定其他文件",
+        }
+    }
+
+    pub fn scan_summary(&self) -> &'static str {
+        match self.lang {
+            Language::English => "📊 Scan summary for slots",
+            Language::Chinese => "📊 扫描汇总，slot范围",
+        }
+    }
+
+    pub fn scan_incomplete(&self) -> &'static str {
+        match self.lang {
+            Language::English => "⚠️ Scan incomplete; run the same command again to resume from slot",
+            Language::Chinese => "⚠️ 扫描未完成，再次运行相同命令将从此slot继续:",
+        }
+    }
+
+    pub fn scanned_slots(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Blocks scanned:",
+            Language::Chinese => "  已扫描区块:",
+        }
+    }
+
+    pub fn skipped_slots(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Skipped slots (no block):",
+            Language::Chinese => "  被跳过的slot（无区块）:",
+        }
+    }
+
+    pub fn failed_slots(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Failed slots (retried on resume):",
+            Language::Chinese => "  获取失败的slot（续扫时重试）:",
+        }
+    }
+
+    pub fn total_sandwiches(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Sandwich attacks:",
+            Language::Chinese => "  三明治攻击次数:",
+        }
+    }
+
+    pub fn total_failed_attempts(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Failed attack attempts:",
+            Language::Chinese => "  失败的攻击尝试:",
+        }
+    }
+
+    pub fn total_victims(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Victim transactions:",
+            Language::Chinese => "  受害交易数:",
+        }
+    }
+
+    pub fn total_victim_loss(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Total victim losses:",
+            Language::Chinese => "  受害者总损失:",
+        }
+    }
+
+    pub fn sandwiches_per_slot(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Sandwiches per slot:",
+            Language::Chinese => "各slot的三明治攻击次数:",
+        }
+    }
+
+    pub fn top_attackers(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Top attackers (sandwiches / victims / victim losses):",
+            Language::Chinese => "主要攻击者（攻击次数 / 受害交易数 / 受害者损失）:",
+        }
+    }
+
+    pub fn invalid_limit(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Invalid --limit, expected a number:",
+            Language::Chinese => "无效的--limit，应为数字:",
+        }
+    }
+
+    pub fn audit_start(&self) -> &'static str {
+        match self.lang {
+            Language::English => "🔍 Auditing MEV losses of wallet {}...",
+            Language::Chinese => "🔍 审计钱包 {} 遭受的MEV损失...",
+        }
+    }
+
+    pub fn get_signatures_failed(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Failed to get the address's transaction signatures:",
+            Language::Chinese => "获取地址交易签名失败:",
+        }
+    }
+
+    pub fn audit_report(&self) -> &'static str {
+        match self.lang {
+            Language::English => "📊 MEV loss report for",
+            Language::Chinese => "📊 MEV损失报告:",
+        }
+    }
+
+    pub fn audit_signatures(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Transactions checked:",
+            Language::Chinese => "  检查的交易数:",
+        }
+    }
+
+    pub fn audit_swaps(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Swaps analyzed:",
+            Language::Chinese => "  分析的DEX交易数:",
+        }
+    }
+
+    pub fn audit_swaps_unavailable(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Swaps skipped (block unavailable):",
+            Language::Chinese => "  无法获取区块而跳过的DEX交易:",
+        }
+    }
+
+    pub fn audit_frontruns(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Front-run attacks:",
+            Language::Chinese => "  抢跑攻击次数:",
+        }
+    }
+
+    pub fn audit_per_token(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Losses per token (amount [attacks]):",
+            Language::Chinese => "按代币汇总的损失（数量 [攻击次数]）:",
+        }
+    }
+
+    pub fn audit_per_month(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Per month, UTC (sandwiches / front-runs / losses):",
+            Language::Chinese => "按月汇总，UTC（三明治 / 抢跑 / 损失）:",
+        }
+    }
+
+    pub fn audit_attacks(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Attacked transactions:",
+            Language::Chinese => "遭受攻击的交易:",
+        }
+    }
+
+    pub fn pool_audit_start(&self) -> &'static str {
+        match self.lang {
+            Language::English => "🔍 Auditing sandwich attacks against pool {}...",
+            Language::Chinese => "🔍 审计池子 {} 遭受的三明治攻击...",
+        }
+    }
+
+    pub fn pool_audit_report(&self) -> &'static str {
+        match self.lang {
+            Language::English => "📊 Sandwich report for pool",
+            Language::Chinese => "📊 池子三明治攻击报告:",
+        }
+    }
+
+    pub fn total_extracted(&self) -> &'static str {
+        match self.lang {
+            Language::English => "  Total extracted value:",
+            Language::Chinese => "  累计被提取价值:",
+        }
+    }
+
+    pub fn extracted_per_token(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Extracted value per token (amount [attacks]):",
+            Language::Chinese => "按代币汇总的被提取价值（数量 [攻击次数]）:",
+        }
+    }
+
+    pub fn sandwiches_per_month(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Sandwiches per month, UTC:",
+            Language::Chinese => "按月统计的三明治攻击次数（UTC）:",
+        }
+    }
+
+    pub fn pool_victims(&self) -> &'static str {
+        match self.lang {
+            Language::English => "Victims (transactions / losses):",
+            Language::Chinese => "受害地址（被夹交易数 / 损失）:",
+        }
+    }
+
     pub fn back_tx(&self) -> &'static str {
         match self.lang {
             Language::English => "  Back-run transaction: https://solscan.io/tx/",
@@ -301,13 +595,6 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
-    pub fn frontrun_skipped(&self) -> &'static str {
-        match self.lang {
-            Language::English => "  ℹ️ Front-run detection skipped (to avoid duplicate reporting)",
-            Language::Chinese => "  ℹ️ 已跳过抢跑检测（避免重复报告）",
-        }
-    }
-
     pub fn frontrun_detected(&self) -> &'static str {
         match self.lang {
             Language::English => "
@@ -333,15 +620,6 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
-    pub fn note(&self) -> &'static str {
-        match self.lang {
-            Language::English => "
-⚠️ Note: Detection results are for reference only. Please verify with actual transaction data.",
-            Language::Chinese => "
-⚠️ 注意: 检测结果仅供参考，建议结合实际交易数据验证",
-        }
-    }
-
     pub fn no_jito_tip(&self) -> &'static str {
         match self.lang {
             Language::English => "✅ No Jito tip transaction found.",
@@ -399,11 +677,4 @@ Please enter a Solana transaction hash (or 'exit'/'quit' to close):",
         }
     }
 
-    pub fn calculating_sandwich_loss(&self) -> &'static str {
-        match self.lang {
-            Language::English => "Calculating sandwich attack loss",
-            Language::Chinese => "开始计算三明治攻击损失",
-        }
-    }
-
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// Anchor `emit_cpi!`自调用指令的前缀（EVENT_IX_TAG的小端字节）
+const EVENT_IX_TAG_LE: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// 单条程序日志，已归属到输出它的程序
+#[derive(Debug, Clone)]
+pub struct ProgramLog {
+    pub program_id: String,
+    pub kind: ProgramLogKind,
+}
+
+/// 程序日志的类型
+#[derive(Debug, Clone)]
+pub enum ProgramLogKind {
+    /// `Program log: ...` 文本日志
+    Message(String),
+    /// `Program data: ...` 二进制数据（Anchor `emit!`事件）
+    Data(Vec<u8>),
+}
+
+/// 从日志中解码出的swap事件
+///
+/// 解码器填写实际成交数量、池子地址以及可选的场所专有数据；
+/// `program_id`和`decoder`由[`DecoderRegistry`](crate::decoders::DecoderRegistry)按日志的归属填写，
+/// 用于把事件对应到同一解码器解析出的swap指令。
+#[derive(Debug, Clone)]
+pub struct SwapEvent {
+    /// 输出该事件的程序ID
+    pub program_id: String,
+    /// 解码该事件的解码器名称（`SwapDecoder::name`）
+    pub decoder: &'static str,
+    /// 事件中记录的池子地址（部分场所的事件不包含池子地址）
+    pub pool: Option<String>,
+    /// 实际成交的输入数量
+    pub amount_in: u64,
+    /// 实际成交的输出数量
+    pub amount_out: u64,
+    venue_data: Option<Arc<dyn VenueEventData>>,
+}
+
+/// 场所专有的事件数据，由解码器附加到[`SwapEvent`]上，再通过[`SwapEvent::venue_data`]取回
+pub trait VenueEventData: Any + fmt::Debug + Send + Sync {}
+
+impl<T: Any + fmt::Debug + Send + Sync> VenueEventData for T {}
+
+impl SwapEvent {
+    /// 只包含成交数量的事件
+    pub fn new(amount_in: u64, amount_out: u64) -> Self {
+        Self {
+            program_id: String::new(),
+            decoder: "",
+            pool: None,
+            amount_in,
+            amount_out,
+            venue_data: None,
+        }
+    }
+
+    /// 设置事件中记录的池子地址
+    pub fn with_pool(mut self, pool: String) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// 附加场所专有数据
+    pub fn with_venue_data<T: VenueEventData>(mut self, data: T) -> Self {
+        self.venue_data = Some(Arc::new(data));
+        self
+    }
+
+    /// 取回场所专有数据，类型不符时返回`None`
+    pub fn venue_data<T: VenueEventData>(&self) -> Option<&T> {
+        let data: &dyn Any = self.venue_data.as_deref()?;
+        data.downcast_ref()
+    }
+}
+
+/// 按调用栈将日志归属到各个程序
+pub fn parse_program_logs(log_messages: &[String]) -> Vec<ProgramLog> {
+    let mut logs = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+
+    for line in log_messages {
+        if let Some(message) = line.strip_prefix("Program log: ") {
+            if let Some(program_id) = stack.last() {
+                logs.push(ProgramLog {
+                    program_id: program_id.clone(),
+                    kind: ProgramLogKind::Message(message.to_string()),
+                });
+            }
+        } else if let Some(data) = line.strip_prefix("Program data: ") {
+            if let Some(program_id) = stack.last() {
+                // 一行中可能包含多段以空格分隔的base64数据
+                let mut bytes = Vec::new();
+                for part in data.split_whitespace() {
+                    if let Ok(decoded) = BASE64.decode(part) {
+                        bytes.extend(decoded);
+                    }
+                }
+                logs.push(ProgramLog {
+                    program_id: program_id.clone(),
+                    kind: ProgramLogKind::Data(bytes),
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let program_id = parts.next().unwrap_or_default();
+            match parts.next() {
+                Some("invoke") => stack.push(program_id.to_string()),
+                Some("success") | Some("failed:") => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    logs
+}
+
+/// 取出Anchor `emit_cpi!`自调用指令中的事件数据
+///
+/// 指令数据布局: EVENT_IX_TAG_LE(8字节) + 事件鉴别器(8字节) + borsh数据
+pub fn cpi_event_payload(instruction_data: &str) -> Option<Vec<u8>> {
+    let data = bs58::decode(instruction_data).into_vec().ok()?;
+    data.strip_prefix(&EVENT_IX_TAG_LE).map(<[u8]>::to_vec)
+}
+
+/// 顺序读取borsh编码数据的辅助结构
+pub struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(self.offset..self.offset + N)?.try_into().ok()?;
+        self.offset += N;
+        Some(bytes)
+    }
+
+    /// 跳过n个字节
+    pub fn skip(&mut self, n: usize) -> &mut Self {
+        self.offset += n;
+        self
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|b| b[0])
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        self.read_u8().map(|b| b != 0)
+    }
+
+    pub fn read_u64(&mut self) -> Option<u64> {
+        self.take::<8>().map(u64::from_le_bytes)
+    }
+
+    pub fn read_i64(&mut self) -> Option<i64> {
+        self.take::<8>().map(i64::from_le_bytes)
+    }
+
+    pub fn read_u128(&mut self) -> Option<u128> {
+        self.take::<16>().map(u128::from_le_bytes)
+    }
+
+    pub fn read_pubkey(&mut self) -> Option<String> {
+        self.take::<32>().map(|b| bs58::encode(b).into_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTER: &str = "Outer11111111111111111111111111111111111111";
+    const INNER: &str = "Inner11111111111111111111111111111111111111";
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn attributes_logs_to_the_invoking_program() {
+        let logs = parse_program_logs(&lines(&[
+            &format!("Program {} invoke [1]", OUTER),
+            "Program log: Instruction: Route",
+            &format!("Program {} invoke [2]", INNER),
+            "Program log: inner message",
+            // 两段base64数据: [1, 2] 和 [3]
+            "Program data: AQI= Aw==",
+            &format!("Program {} consumed 1200 of 200000 compute units", INNER),
+            &format!("Program {} success", INNER),
+            "Program log: back in outer",
+            &format!("Program {} failed: custom program error: 0x1", OUTER),
+            "Program log: no program on the stack",
+        ]));
+
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0].program_id, OUTER);
+        assert!(matches!(&logs[0].kind, ProgramLogKind::Message(m) if m == "Instruction: Route"));
+        assert_eq!(logs[1].program_id, INNER);
+        assert!(matches!(&logs[1].kind, ProgramLogKind::Message(m) if m == "inner message"));
+        assert_eq!(logs[2].program_id, INNER);
+        assert!(matches!(&logs[2].kind, ProgramLogKind::Data(d) if d == &[1, 2, 3]));
+        assert_eq!(logs[3].program_id, OUTER);
+        assert!(matches!(&logs[3].kind, ProgramLogKind::Message(m) if m == "back in outer"));
+    }
+
+    #[test]
+    fn strips_emit_cpi_tag() {
+        let mut data = EVENT_IX_TAG_LE.to_vec();
+        data.extend([9, 8, 7]);
+        let encoded = bs58::encode(&data).into_string();
+        assert_eq!(cpi_event_payload(&encoded), Some(vec![9, 8, 7]));
+
+        // 普通指令数据没有EVENT_IX_TAG前缀
+        let encoded = bs58::encode([1u8, 2, 3, 4, 5, 6, 7, 8, 9]).into_string();
+        assert_eq!(cpi_event_payload(&encoded), None);
+    }
+
+    #[test]
+    fn borsh_reader_reads_little_endian_fields() {
+        let mut data = vec![7u8, 1];
+        data.extend(0x0102_0304_0506_0708u64.to_le_bytes());
+        data.extend((-2i64).to_le_bytes());
+        data.extend(u128::MAX.to_le_bytes());
+        data.extend([5u8; 32]);
+        data.extend([0xaa, 0xbb]);
+
+        let mut reader = BorshReader::new(&data);
+        assert_eq!(reader.read_u8(), Some(7));
+        assert_eq!(reader.read_bool(), Some(true));
+        assert_eq!(reader.read_u64(), Some(0x0102_0304_0506_0708));
+        assert_eq!(reader.read_i64(), Some(-2));
+        assert_eq!(reader.read_u128(), Some(u128::MAX));
+        assert_eq!(reader.read_pubkey(), Some(bs58::encode([5u8; 32]).into_string()));
+        assert_eq!(reader.skip(1).read_u8(), Some(0xbb));
+        // 数据不足时返回None
+        assert_eq!(reader.read_u8(), None);
+        assert_eq!(BorshReader::new(&[1, 2, 3]).read_u64(), None);
+    }
+
+    #[test]
+    fn venue_data_downcasts_to_the_attached_type() {
+        #[derive(Debug)]
+        struct Reserves(u64);
+
+        let event = SwapEvent::new(10, 9).with_pool("pool".to_string()).with_venue_data(Reserves(42));
+        assert_eq!((event.amount_in, event.amount_out), (10, 9));
+        assert_eq!(event.pool.as_deref(), Some("pool"));
+        assert_eq!(event.venue_data::<Reserves>().map(|r| r.0), Some(42));
+        assert!(event.venue_data::<u64>().is_none());
+        assert!(SwapEvent::new(1, 1).venue_data::<Reserves>().is_none());
+    }
+}
@@ -1,16 +1,25 @@
 use config::{Config, File};
 use std::io::{self, Write};
+use std::path::Path;
 
+mod audit;
+mod cache;
 mod client;
+mod decoders;
 mod locale;
+mod logs;
 mod mev;
+mod rate_limit;
+mod rpc_pool;
+mod scan;
 mod settings;
 
-use crate::client::SolanaClient;
+use crate::client::{ClientError, SolanaClient};
 use crate::locale::Locale;
 use crate::mev::MevDetector;
-use crate::settings::Settings;
-use log::{error, info};
+use crate::scan::ScanCheckpoint;
+use crate::settings::{Commitment, Settings};
+use log::{debug, error, info};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,7 +27,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .add_source(File::with_name("config"))
         .build()?;
 
-    let settings: Settings = config.try_deserialize()?;
+    let args: Vec<String> = std::env::args().collect();
+    let mut settings: Settings = config.try_deserialize()?;
+    if args.iter().any(|arg| arg == "--offline") {
+        settings.cache.offline = true;
+    }
+    // 批量模式（整块/slot范围扫描、钱包/池子审计）不逐个确认交易状态，
+    // 严格模式下直接按finalized查询账本，只分析已finalized的区块和交易
+    let bulk_mode = ["--scan-block", "--scan-range", "--audit-wallet", "--audit-pool"]
+        .iter()
+        .any(|flag| args.iter().any(|arg| arg == flag));
+    if settings.mev_detection.finalized_only && bulk_mode {
+        settings.rpc.commitment = Commitment::Finalized;
+    }
+    // --json: 扫描模式下只向标准输出打印JSON结果
+    let json_output = args.iter().any(|arg| arg == "--json");
     let locale = Locale::new(settings.language.clone());
 
     env_logger::Builder::from_env(
@@ -27,14 +50,80 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     .format_timestamp_secs()
     .init();
 
+    // JSON输出时提示信息写到标准错误，保持标准输出为纯JSON
+    let notice = |message: &str| {
+        if json_output {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+
     info!("{}", locale.starting());
-    println!("{}", "=".repeat(60));
-    println!("{}", locale.title());
-    println!("{}", "=".repeat(60));
+    notice(&"=".repeat(60));
+    notice(locale.title());
+    notice(&"=".repeat(60));
 
-    let client = SolanaClient::new(settings.rpc_url.clone())?;
+    let client = SolanaClient::new(&settings.rpc_endpoints(), &settings.rpc, &settings.cache)?;
+    if settings.cache.offline {
+        notice(locale.offline_mode());
+    } else if client.check_endpoints().await == 0 {
+        notice(locale.rpc_suggestion());
+    }
     let detector = MevDetector::new(settings.mev_detection.clone(), settings.language.clone());
 
+    // --scan-block <slot>: 扫描整个区块后退出
+    if let Some(slot) = arg_value(&args, "--scan-block") {
+        let slot: u64 = match slot.parse() {
+            Ok(slot) => slot,
+            Err(_) => {
+                error!("{} {}", locale.invalid_slot(), slot);
+                return Err(format!("invalid slot: {}", slot).into());
+            }
+        };
+        return scan_block(&client, &detector, slot, &locale, json_output).await;
+    }
+
+    // --audit-wallet <地址> / --audit-pool <池子地址>: 审计该地址最近的交易，--limit指定最多检查的交易数
+    let audit_wallet_address = arg_value(&args, "--audit-wallet");
+    let audit_pool_address = arg_value(&args, "--audit-pool");
+    if audit_wallet_address.is_some() || audit_pool_address.is_some() {
+        let max_signatures = match arg_value(&args, "--limit") {
+            Some(limit) => match limit.parse() {
+                Ok(limit) => limit,
+                Err(_) => {
+                    error!("{} {}", locale.invalid_limit(), limit);
+                    return Err(format!("invalid limit: {}", limit).into());
+                }
+            },
+            None => settings.audit.max_signatures,
+        };
+        if let Some(address) = audit_wallet_address {
+            return audit_wallet(&client, &detector, address, max_signatures, &locale, json_output).await;
+        }
+        if let Some(pool) = audit_pool_address {
+            return audit_pool(&client, &detector, pool, max_signatures, &locale, json_output).await;
+        }
+    }
+
+    // --scan-range <起始slot>-<结束slot>: 扫描slot范围（含两端）后输出汇总
+    if let Some(range) = arg_value(&args, "--scan-range") {
+        let Some(slot_range) = parse_slot_range(range) else {
+            error!("{} {}", locale.invalid_slot_range(), range);
+            return Err(format!("invalid slot range: {}", range).into());
+        };
+        let checkpoint_path = arg_value(&args, "--checkpoint").unwrap_or(&settings.scan.checkpoint_file);
+        return scan_range(
+            &client,
+            &detector,
+            slot_range,
+            Path::new(checkpoint_path),
+            settings.scan.concurrency,
+            &locale,
+            json_output,
+        ).await;
+    }
+
     if !settings.auto_detect_hashes.is_empty() {
         println!(
             "{} {}",
@@ -125,11 +214,19 @@ async fn analyze_transaction(
         Ok(tx) => tx,
         Err(e) => {
             error!("{} {}", locale.get_tx_failed(), e);
+            report_client_error(&e, locale);
             return Err(e.into());
         }
     };
 
-    info!("{} {}", locale.get_tx_success(), target_tx.slot);
+    info!("{} {} ({})", locale.get_tx_success(), target_tx.slot,
+          target_tx.served_by.as_deref().unwrap_or("-"));
+
+    // 失败的交易没有实际成交，不进入检测流程
+    if let Some(err) = target_tx.execution_error() {
+        println!("{} {}", locale.target_tx_failed(), err);
+        return Ok(());
+    }
 
     // 步骤2: 检查是否为简单转账
     if detector.is_simple_transfer(&target_tx) {
@@ -150,22 +247,33 @@ async fn analyze_transaction(
         // 忽略Jito模式 - 直接基于账户重合分析
         println!("🔧 忽略Jito模式已开启，使用账户重合分析方法");
         
-        let (nearby_transactions, target_index) = match client.get_nearby_transactions(target_signature).await {
+        let (nearby_transactions, target_index) = match client.get_nearby_transactions(
+            target_signature,
+            settings.mev_detection.window_transactions,
+            settings.mev_detection.window_slots,
+        ).await {
             Ok(result) => result,
             Err(e) => {
                 error!("{} {}", locale.get_nearby_failed(), e);
-                println!("{}", locale.rpc_suggestion());
+                report_client_error(&e, locale);
                 return Err(e.into());
             }
         };
 
         println!("{}",locale.analyzing_nearby().replace("{}", &nearby_transactions.len().to_string()));
+        println!("{}", locale.target_block_position().replace("{}", &tx_position(nearby_transactions[target_index].position())));
         
         // 基于纯账户重合进行MEV分析（不检查Jito小费）
         analyze_account_overlap_mev(&client, &detector, &nearby_transactions, target_index, target_signature, &locale).await?;
+        report_failed_attempts(detector, &nearby_transactions, target_signature, locale);
     } else {
-        // 正常模式 - 优先使用Jito API查询束包
-        let bundle_result = detector.check_jito_bundle_api(target_signature).await;
+        // 正常模式 - 优先使用Jito API查询束包（离线模式不访问Jito API）
+        let bundle_result = if settings.cache.offline {
+            println!("{}", locale.jito_api_offline());
+            None
+        } else {
+            detector.check_jito_bundle_api(target_signature).await
+        };
         
         match bundle_result {
             Some(bundle_info) => {
@@ -197,23 +305,328 @@ async fn analyze_transaction(
             }
             None => {
                 // Jito API查不到，使用传统方法
-                println!("Jito API未找到束包，使用传统分析方法");
+                if !settings.cache.offline {
+                    println!("Jito API未找到束包，使用传统分析方法");
+                }
                 
-                let (nearby_transactions, target_index) = match client.get_nearby_transactions(target_signature).await {
+                let (nearby_transactions, target_index) = match client.get_nearby_transactions(
+                    target_signature,
+                    settings.mev_detection.window_transactions,
+                    settings.mev_detection.window_slots,
+                ).await {
                     Ok(result) => result,
                     Err(e) => {
                         error!("{} {}", locale.get_nearby_failed(), e);
-                        println!("{}", locale.rpc_suggestion());
+                        report_client_error(&e, locale);
                         return Err(e.into());
                     }
                 };
 
                 println!("{}",locale.analyzing_nearby().replace("{}", &nearby_transactions.len().to_string()));
+                println!("{}", locale.target_block_position().replace("{}", &tx_position(nearby_transactions[target_index].position())));
                 
                 // 基于附近交易进行MEV分析
                 analyze_traditional_mev(&client, &detector, &nearby_transactions, target_index, target_signature, &locale).await?;
+                report_failed_attempts(detector, &nearby_transactions, target_signature, locale);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 命令行参数`name`后面的值
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+/// 解析`起始slot-结束slot`，要求起始不大于结束
+fn parse_slot_range(range: &str) -> Option<(u64, u64)> {
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+    (start <= end).then_some((start, end))
+}
+
+/// 审计钱包地址并输出按代币和月份汇总的损失报告
+async fn audit_wallet(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    address: &str,
+    max_signatures: usize,
+    locale: &Locale,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !json_output {
+        println!("{}", locale.audit_start().replace("{}", address));
+    }
+
+    let report = match audit::audit_wallet(client, detector, address, max_signatures).await {
+        Ok(report) => report,
+        Err(e) => {
+            error!("{} {}", locale.get_signatures_failed(), e);
+            report_client_error(&e, locale);
+            return Err(e.into());
+        }
+    };
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("{} {}", locale.audit_report(), report.address);
+    println!("{}", "=".repeat(60));
+    println!("{} {}", locale.audit_signatures(), report.signatures_scanned);
+    println!("{} {}", locale.audit_swaps(), report.swaps_analyzed);
+    if report.swaps_unavailable > 0 {
+        println!("{} {}", locale.audit_swaps_unavailable(), report.swaps_unavailable);
+    }
+    let sandwiches = report.attacks.iter().filter(|a| a.kind == audit::AttackKind::Sandwich).count();
+    println!("{} {}", locale.total_sandwiches(), sandwiches);
+    println!("{} {}", locale.audit_frontruns(), report.attacks.len() - sandwiches);
+    println!("{} {:.9} SOL", locale.total_victim_loss(), report.total_loss_lamports as f64 / 1_000_000_000.0);
+
+    if !report.per_token.is_empty() {
+        println!("\n{}", locale.audit_per_token());
+        for token in &report.per_token {
+            println!("  {} ({}): {:.6}  [{}]", token.token_symbol, token.token_address,
+                     token.loss_amount_ui, token.attacks);
+        }
+    }
+
+    if !report.per_month.is_empty() {
+        println!("\n{}", locale.audit_per_month());
+        for (month, summary) in &report.per_month {
+            println!("  {}: {} / {} / {:.9} SOL", month, summary.sandwiches, summary.frontruns,
+                     summary.loss_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    if !report.attacks.is_empty() {
+        println!("\n{}", locale.audit_attacks());
+        for attack in &report.attacks {
+            let kind = match attack.kind {
+                audit::AttackKind::Sandwich => locale.sandwich_detected().trim(),
+                audit::AttackKind::Frontrun => locale.frontrun_detected().trim(),
+            };
+            println!("  {} slot {} {}", kind, attack.slot, attack.signature);
+            match &attack.loss {
+                Some(loss) => println!("    {} {}", locale.loss_amount(), format_loss(loss)),
+                None if attack.kind == audit::AttackKind::Sandwich => println!("    {}", locale.cannot_calculate_loss()),
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 审计池子地址并输出打到该池子上的三明治攻击、受害者、攻击者和被提取的价值
+async fn audit_pool(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    pool: &str,
+    max_signatures: usize,
+    locale: &Locale,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !json_output {
+        println!("{}", locale.pool_audit_start().replace("{}", pool));
+    }
+
+    let report = match audit::audit_pool(client, detector, pool, max_signatures).await {
+        Ok(report) => report,
+        Err(e) => {
+            error!("{} {}", locale.get_signatures_failed(), e);
+            report_client_error(&e, locale);
+            return Err(e.into());
+        }
+    };
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("{} {}", locale.pool_audit_report(), report.pool);
+    println!("{}", "=".repeat(60));
+    println!("{} {}", locale.audit_signatures(), report.signatures_scanned);
+    println!("{} {}", locale.audit_swaps(), report.swaps_analyzed);
+    if report.swaps_unavailable > 0 {
+        println!("{} {}", locale.audit_swaps_unavailable(), report.swaps_unavailable);
+    }
+    println!("{} {}", locale.total_sandwiches(), report.sandwiches.len());
+    println!("{} {} ({:.2}%)", locale.total_victims(), report.total_victims, report.victim_ratio * 100.0);
+    println!("{} {:.9} SOL", locale.total_extracted(), report.total_extracted_lamports as f64 / 1_000_000_000.0);
+
+    if !report.per_token.is_empty() {
+        println!("\n{}", locale.extracted_per_token());
+        for token in &report.per_token {
+            println!("  {} ({}): {:.6}  [{}]", token.token_symbol, token.token_address,
+                     token.loss_amount_ui, token.attacks);
+        }
+    }
+
+    if !report.sandwiches_per_month.is_empty() {
+        println!("\n{}", locale.sandwiches_per_month());
+        for (month, count) in &report.sandwiches_per_month {
+            println!("  {}: {}", month, count);
+        }
+    }
+
+    if !report.attackers.is_empty() {
+        println!("\n{}", locale.top_attackers());
+        for (i, attacker) in report.attackers.iter().enumerate() {
+            println!("  {}. {}  {} / {} / {:.9} SOL", i + 1, attacker.attacker, attacker.sandwiches,
+                     attacker.victims, attacker.victim_loss_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    if !report.victims.is_empty() {
+        println!("\n{}", locale.pool_victims());
+        for (i, victim) in report.victims.iter().enumerate() {
+            println!("  {}. {}  {} / {:.9} SOL", i + 1, victim.address, victim.transactions,
+                     victim.loss_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    for (i, sandwich) in report.sandwiches.iter().enumerate() {
+        println!("\n{} #{}  (slot {})", locale.sandwich_detected().trim(), i + 1, sandwich.slot);
+        println!("{} {}", locale.attacker(), sandwich.attacker);
+        println!("{}{}", locale.front_tx(), sandwich.front_tx);
+        for victim in &sandwich.victims {
+            println!("{}{}", locale.victim_tx(), victim.signature);
+            match &victim.loss {
+                Some(loss) => println!("  {} {}", locale.loss_amount(), format_loss(loss)),
+                None => println!("  {}", locale.cannot_calculate_loss()),
             }
         }
+        println!("{}{}", locale.back_tx(), sandwich.back_tx);
+    }
+
+    Ok(())
+}
+
+/// 扫描slot范围，支持从检查点继续，结束后输出汇总
+async fn scan_range(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    (start_slot, end_slot): (u64, u64),
+    checkpoint_path: &Path,
+    concurrency: usize,
+    locale: &Locale,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checkpoint = match ScanCheckpoint::load(checkpoint_path)? {
+        Some(checkpoint) if checkpoint.start_slot == start_slot && checkpoint.end_slot == end_slot => {
+            info!("{} {} ({})", locale.scan_resumed(), checkpoint.next_slot, checkpoint_path.display());
+            checkpoint
+        }
+        Some(checkpoint) => {
+            error!("{} {}: {}-{}", locale.checkpoint_range_mismatch(), checkpoint_path.display(),
+                   checkpoint.start_slot, checkpoint.end_slot);
+            return Err("checkpoint belongs to a different slot range".into());
+        }
+        None => ScanCheckpoint::new(start_slot, end_slot),
+    };
+
+    scan::scan_slot_range(client, detector, &mut checkpoint, checkpoint_path, concurrency).await?;
+
+    let summary = checkpoint.summary();
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("{} {} - {}", locale.scan_summary(), summary.start_slot, summary.end_slot);
+    if !checkpoint.is_complete() {
+        println!("{} {}", locale.scan_incomplete(), checkpoint.next_slot);
+    }
+    println!("{}", "=".repeat(60));
+    println!("{} {}", locale.scanned_slots(), summary.scanned_slots);
+    println!("{} {}", locale.skipped_slots(), summary.skipped_slots);
+    if !summary.failed_slots.is_empty() {
+        let failed: Vec<String> = summary.failed_slots.iter().map(u64::to_string).collect();
+        println!("{} {}", locale.failed_slots(), failed.join(", "));
+    }
+    println!("{} {}", locale.total_sandwiches(), summary.total_sandwiches);
+    println!("{} {}", locale.total_victims(), summary.total_victims);
+    println!("{} {:.9} SOL", locale.total_victim_loss(),
+             summary.total_victim_loss_lamports as f64 / 1_000_000_000.0);
+    println!("{} {}", locale.total_failed_attempts(), summary.total_failed_attempts);
+
+    if !summary.sandwiches_per_slot.is_empty() {
+        println!("\n{}", locale.sandwiches_per_slot());
+        for (slot, count) in &summary.sandwiches_per_slot {
+            println!("  {}: {}", slot, count);
+        }
+    }
+
+    if !summary.top_attackers.is_empty() {
+        println!("\n{}", locale.top_attackers());
+        for (i, attacker) in summary.top_attackers.iter().enumerate() {
+            println!("  {}. {}  {} / {} / {:.9} SOL", i + 1, attacker.attacker, attacker.sandwiches,
+                     attacker.victims, attacker.victim_loss_lamports as f64 / 1_000_000_000.0);
+        }
+    }
+
+    Ok(())
+}
+
+/// 扫描整个区块中的三明治攻击并输出结果
+async fn scan_block(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    slot: u64,
+    locale: &Locale,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !json_output {
+        println!("{}", locale.block_scan_start().replace("{}", &slot.to_string()));
+    }
+
+    let sandwiches = match detector.scan_block(client, slot).await {
+        Ok(sandwiches) => sandwiches,
+        Err(e) => {
+            error!("{} {}", locale.get_block_failed(), e);
+            report_client_error(&e, locale);
+            return Err(e.into());
+        }
+    };
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&sandwiches)?);
+        return Ok(());
+    }
+
+    println!("{} {}", locale.block_scan_result(), sandwiches.len());
+    for (i, sandwich) in sandwiches.iter().enumerate() {
+        println!("\n{} #{}", locale.sandwich_detected().trim(), i + 1);
+        println!("{} {}", locale.attacker(), sandwich.attacker);
+        println!("{}{}  ({})", locale.front_tx(), sandwich.front.signature,
+                 tx_position((sandwich.front.slot, sandwich.front.block_index)));
+        for victim in &sandwich.victims {
+            let tx = &victim.transaction;
+            println!("{}{}  ({})", locale.victim_tx(), tx.signature, tx_position((tx.slot, tx.block_index)));
+            match &victim.loss {
+                Some(loss) => display_loss_results(loss, locale),
+                None => println!("{}", locale.cannot_calculate_loss()),
+            }
+            if !victim.failed_attempts.is_empty() {
+                print_failed_attempts(&victim.failed_attempts, locale);
+            }
+        }
+        println!("{}{}  ({})", locale.back_tx(), sandwich.back.signature,
+                 tx_position((sandwich.back.slot, sandwich.back.block_index)));
+        if !sandwich.pools.is_empty() {
+            println!("{} {}", locale.attacked_pools(), sandwich.pools.join(", "));
+        }
     }
 
     Ok(())
@@ -232,6 +645,9 @@ async fn analyze_bundle_mev(
     
     // 检测三明治攻击
     if let Some(sandwich) = detector.detect_sandwich_attack(&bundle_transactions, target_signature) {
+        if !sandwich_finalized(client, detector, &[&sandwich.front_tx, target_signature, &sandwich.back_tx], locale).await {
+            return Ok(());
+        }
         println!("{}", locale.sandwich_detected());
         println!("{}{}", locale.front_tx(), sandwich.front_tx);
         println!("{}{}", locale.back_tx(), sandwich.back_tx);
@@ -247,6 +663,7 @@ async fn analyze_bundle_mev(
     } else if let Some(frontrun) = detector.detect_frontrun_attack(&bundle_transactions, target_signature) {
         println!("{}", locale.frontrun_detected());
         println!("{} {}", locale.frontrun_tx(), frontrun.front_tx);
+        println!("{} {}", locale.shared_accounts(), frontrun.account_intersection.len());
         
         // 抢跑攻击的损失计算逻辑可以简化或跳过
         println!("抢跑攻击损失计算待实现");
@@ -275,6 +692,9 @@ async fn analyze_traditional_mev(
             
             // 检测三明治攻击
             if let Some(sandwich) = detector.detect_sandwich_attack(&bundle_transactions, target_signature) {
+                if !sandwich_finalized(client, detector, &[&sandwich.front_tx, target_signature, &sandwich.back_tx], locale).await {
+                    return Ok(());
+                }
                 println!("{}", locale.sandwich_detected());
                 println!("{}{}", locale.front_tx(), sandwich.front_tx);
                 println!("{}{}", locale.back_tx(), sandwich.back_tx);
@@ -290,6 +710,7 @@ async fn analyze_traditional_mev(
             } else if let Some(frontrun) = detector.detect_frontrun_attack(&bundle_transactions, target_signature) {
                 println!("{}", locale.frontrun_detected());
                 println!("{} {}", locale.frontrun_tx(), frontrun.front_tx);
+                println!("{} {}", locale.shared_accounts(), frontrun.account_intersection.len());
                 println!("抢跑攻击损失计算待实现");
             } else {
                 println!("{}", locale.no_mev_detected());
@@ -319,10 +740,7 @@ async fn analyze_account_overlap_mev(
     
     // 获取目标交易的账户列表
     let target_tx = &nearby_transactions[target_index];
-    let target_accounts: Vec<String> = target_tx.transaction.message.account_keys
-        .iter()
-        .map(|key| key.to_string())
-        .collect();
+    let target_accounts: Vec<String> = target_tx.account_keys();
     
     println!("🎯 目标交易涉及 {} 个账户", target_accounts.len());
     
@@ -330,10 +748,7 @@ async fn analyze_account_overlap_mev(
     let mut potential_front_txs = Vec::new();
     for i in 0..target_index {
         let tx = &nearby_transactions[i];
-        let tx_accounts: Vec<String> = tx.transaction.message.account_keys
-            .iter()
-            .map(|key| key.to_string())
-            .collect();
+        let tx_accounts: Vec<String> = tx.account_keys();
         
         // 计算账户重合度
         let overlap_count = target_accounts.iter()
@@ -342,10 +757,10 @@ async fn analyze_account_overlap_mev(
         
         let overlap_ratio = overlap_count as f64 / target_accounts.len() as f64;
         
-        // 如果重合度超过阈值，认为可能是前置攻击交易
-        if overlap_ratio >= 0.3 && detector.is_dex_transaction(tx) {
+        // 如果重合度超过阈值，认为可能是前置攻击交易（失败的交易单独报告）
+        if overlap_ratio >= 0.3 && !tx.is_failed() && detector.is_dex_transaction(tx) {
             potential_front_txs.push((i, tx.transaction.signatures[0].to_string(), overlap_ratio));
-            println!("  ⬆️  前置交易 {}: 重合度 {:.1}%", &tx.transaction.signatures[0].to_string()[0..8], overlap_ratio * 100.0);
+            println!("  ⬆️  前置交易 {} ({}): 重合度 {:.1}%", &tx.transaction.signatures[0].to_string()[0..8], tx_position(tx.position()), overlap_ratio * 100.0);
         }
     }
     
@@ -353,10 +768,7 @@ async fn analyze_account_overlap_mev(
     let mut potential_back_txs = Vec::new();
     for i in (target_index + 1)..nearby_transactions.len() {
         let tx = &nearby_transactions[i];
-        let tx_accounts: Vec<String> = tx.transaction.message.account_keys
-            .iter()
-            .map(|key| key.to_string())
-            .collect();
+        let tx_accounts: Vec<String> = tx.account_keys();
         
         // 计算账户重合度
         let overlap_count = target_accounts.iter()
@@ -365,10 +777,10 @@ async fn analyze_account_overlap_mev(
         
         let overlap_ratio = overlap_count as f64 / target_accounts.len() as f64;
         
-        // 如果重合度超过阈值，认为可能是后置攻击交易
-        if overlap_ratio >= 0.3 && detector.is_dex_transaction(tx) {
+        // 如果重合度超过阈值，认为可能是后置攻击交易（失败的交易单独报告）
+        if overlap_ratio >= 0.3 && !tx.is_failed() && detector.is_dex_transaction(tx) {
             potential_back_txs.push((i, tx.transaction.signatures[0].to_string(), overlap_ratio));
-            println!("  ⬇️  后置交易 {}: 重合度 {:.1}%", &tx.transaction.signatures[0].to_string()[0..8], overlap_ratio * 100.0);
+            println!("  ⬇️  后置交易 {} ({}): 重合度 {:.1}%", &tx.transaction.signatures[0].to_string()[0..8], tx_position(tx.position()), overlap_ratio * 100.0);
         }
     }
     
@@ -377,6 +789,10 @@ async fn analyze_account_overlap_mev(
         // 选择重合度最高的前置和后置交易
         let best_front = potential_front_txs.iter().max_by(|a, b| a.2.partial_cmp(&b.2).unwrap()).unwrap();
         let best_back = potential_back_txs.iter().max_by(|a, b| a.2.partial_cmp(&b.2).unwrap()).unwrap();
+
+        if !sandwich_finalized(client, detector, &[&best_front.1, target_signature, &best_back.1], locale).await {
+            return Ok(());
+        }
         
         println!("{}", locale.sandwich_detected());
         println!("{}{}  (重合度: {:.1}%)", locale.front_tx(), best_front.1, best_front.2 * 100.0);
@@ -405,29 +821,132 @@ async fn analyze_account_overlap_mev(
     Ok(())
 }
 
-/// 计算MEV损失 - 简化版本，只使用两种方法
+/// 交易位置`slot#区块内原始索引`（过滤投票交易前的索引），索引未知时显示`-`
+fn tx_position((slot, block_index): (u64, Option<usize>)) -> String {
+    match block_index {
+        Some(index) => format!("{}#{}", slot, index),
+        None => format!("{}#-", slot),
+    }
+}
+
+/// 单独列出目标交易附近执行失败的攻击尝试
+fn report_failed_attempts(
+    detector: &MevDetector,
+    nearby_transactions: &[crate::client::Transaction],
+    target_signature: &str,
+    locale: &Locale,
+) {
+    let attempts = detector.detect_failed_attack_attempts(nearby_transactions, target_signature);
+    if !attempts.is_empty() {
+        println!();
+        print_failed_attempts(&attempts, locale);
+    }
+}
+
+/// 输出失败的攻击尝试列表
+fn print_failed_attempts(attempts: &[crate::mev::FailedAttackAttempt], locale: &Locale) {
+    println!("{}", locale.failed_attempts_found().replace("{}", &attempts.len().to_string()));
+    for attempt in attempts {
+        let position = if attempt.is_before_target {
+            locale.failed_attempt_before()
+        } else {
+            locale.failed_attempt_after()
+        };
+        println!("  {} ({}, {}) {}", attempt.signature, tx_position((attempt.slot, attempt.block_index)), position, attempt.error);
+        debug!("共享账户: {:?}", attempt.account_intersection);
+    }
+}
+
+/// 严格模式（`finalized_only`）下检查三明治涉及的交易是否都已finalized
+///
+/// 未开启严格模式时总是返回`true`；任一交易未finalized或节点查不到（所在分叉已被丢弃）时提示并返回`false`。
+async fn sandwich_finalized(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    signatures: &[&str],
+    locale: &Locale,
+) -> bool {
+    if !detector.config.finalized_only {
+        return true;
+    }
+
+    match client.get_signature_statuses(signatures).await {
+        Ok(statuses) => {
+            let mut finalized = statuses.len() == signatures.len();
+            for (signature, status) in signatures.iter().zip(&statuses) {
+                match status {
+                    Some(status) => {
+                        debug!("交易{}位于slot {}，状态: {}", signature, status.slot,
+                               status.confirmation_status.as_deref().unwrap_or("-"));
+                        finalized &= status.is_finalized();
+                    }
+                    None => {
+                        debug!("节点未找到交易{}，可能所在分叉已被丢弃", signature);
+                        finalized = false;
+                    }
+                }
+            }
+            if !finalized {
+                println!("{}", locale.awaiting_finalization());
+            }
+            finalized
+        }
+        Err(e) => {
+            error!("{} {}", locale.finality_check_failed(), e);
+            report_client_error(&e, locale);
+            false
+        }
+    }
+}
+
+/// 根据RPC错误类型给出处理建议
+fn report_client_error(e: &ClientError, locale: &Locale) {
+    if matches!(e, ClientError::NotCached { .. }) {
+        println!("{}", locale.offline_cache_miss());
+    } else if e.is_history_unavailable() {
+        println!("{}", locale.pruned_history());
+    } else if matches!(e, ClientError::NotFound) {
+        println!("{}", locale.tx_not_found());
+    } else {
+        println!("{}", locale.rpc_suggestion());
+    }
+}
+
+/// 计算MEV损失，获取交易失败时给出处理建议
 async fn calculate_mev_loss(
     client: &SolanaClient,
     detector: &MevDetector,
     front_tx_sig: &str,
     target_tx_sig: &str,
     back_tx_sig: &str,
-    _locale: &Locale,
+    locale: &Locale,
 ) -> Option<crate::mev::UserLoss> {
-    // 方法1: 优先使用余额变化分析
-    if let Some(loss) = detector.calculate_precise_sandwich_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await {
-        return Some(loss);
-    }
-    
-    // 方法2: 回退到指令解析分析
-    if let Some(loss) = detector.calculate_instruction_based_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await {
-        return Some(loss);
+    match detector.calculate_sandwich_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await {
+        Ok(loss) => loss,
+        Err(e) if e.is_history_unavailable() => {
+            println!("{}", locale.pruned_history());
+            None
+        }
+        Err(e) => {
+            error!("{} {}", locale.get_tx_failed(), e);
+            report_client_error(&e, locale);
+            None
+        }
     }
-    
-    None
 }
 
 /// 显示损失结果
+/// 以主要损失代币显示的损失数量；没有代币明细时按SOL显示
+fn format_loss(loss: &crate::mev::UserLoss) -> String {
+    loss.token_losses
+        .iter()
+        .find(|token_loss| loss.primary_loss_token.as_ref() == Some(&token_loss.token_address))
+        .map_or_else(
+            || format!("{:.9} SOL", loss.estimated_loss_lamports as f64 / 1_000_000_000.0),
+            |token_loss| format!("{:.9} {}", token_loss.loss_amount_ui, token_loss.token_symbol),
+        )
+}
+
 fn display_loss_results(loss: &crate::mev::UserLoss, locale: &Locale) {
     println!("\n {}", locale.user_loss_estimation());
     
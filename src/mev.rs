@@ -1,6 +1,9 @@
-use crate::client::{Transaction, TransactionWithBalanceChanges, 
+use crate::client::{ClientError, Transaction,
                     AccountBalanceChange, TokenBalanceChange, TransactionMeta, TokenBalance};
 use crate::locale::{Language, Locale};
+use crate::decoders::{DecoderRegistry, InstructionContext};
+use crate::decoders::PumpFunTradeEvent;
+use crate::logs::SwapEvent;
 use crate::settings::MevDetectionConfig;
 use bs58;
 use log::{debug, info};
@@ -10,6 +13,7 @@ use std::collections::HashSet;
 pub struct MevDetector {
     pub config: MevDetectionConfig,
     locale: Locale,
+    decoders: DecoderRegistry,
 }
 
 /// 三明治攻击检测结果
@@ -18,11 +22,20 @@ pub struct SandwichDetails {
     pub front_tx: String,
     pub back_tx: String,
     pub account_intersection: Vec<String>,
-    pub user_loss: Option<UserLoss>,
+}
+
+/// 受害交易两侧的前置/后置交易（交易列表中的索引）
+struct SandwichLegs {
+    front: usize,
+    back: usize,
+    /// 前后账户交集的相似度
+    similarity: f64,
+    /// 前后账户交集的合并
+    account_intersection: Vec<String>,
 }
 
 /// 用户损失分析结果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct UserLoss {
     pub estimated_loss_lamports: u64,
     pub loss_percentage: f64,
@@ -37,7 +50,7 @@ pub struct UserLoss {
 }
 
 /// 代币损失详情
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct TokenLossDetail {
     pub token_address: String,
     pub token_symbol: String,
@@ -47,6 +60,23 @@ pub struct TokenLossDetail {
     pub equivalent_symbol: Option<String>, // 另一个代币的符号
 }
 
+impl UserLoss {
+    /// 以SOL计的损失（lamports）
+    ///
+    /// 主要损失代币不是SOL时，`estimated_loss_lamports`是该代币的最小单位数量，不能按SOL累加；
+    /// 这里只取`token_losses`中的SOL明细，没有代币明细时才使用`estimated_loss_lamports`。
+    pub fn sol_loss_lamports(&self) -> u64 {
+        if self.token_losses.is_empty() {
+            return self.estimated_loss_lamports;
+        }
+        self.token_losses
+            .iter()
+            .filter(|token_loss| token_loss.token_address == WSOL)
+            .map(|token_loss| token_loss.loss_amount)
+            .sum()
+    }
+}
+
 /// Jito束包信息
 #[derive(Debug, Clone)]
 pub struct JitoBundleInfo {
@@ -80,18 +110,6 @@ pub struct BundlePositionAnalysis {
 }
 
 
-/// DEX类型枚举
-#[derive(Debug, Clone, PartialEq)]
-pub enum DexType {
-    Raydium,
-    Orca,
-    Jupiter, 
-    PumpFun,
-    Serum,
-    Unknown,
-}
-
-
 /// 代币流动详情
 #[derive(Debug, Clone)]
 pub struct TokenFlowDetail {
@@ -105,13 +123,56 @@ pub struct TokenFlowDetail {
 /// Swap指令解析结果
 #[derive(Debug, Clone)]
 pub struct SwapInstructionData {
-    pub dex_type: DexType,
+    /// 交易场所名称（解析该swap的解码器名称）
+    pub venue: &'static str,
+    /// 执行该swap的程序ID
+    pub program_id: String,
     pub token_in: String,
     pub token_out: String,
     pub amount_in: u64,
     pub amount_out: u64,
     pub user_address: String,
     pub pool_address: String,
+    /// 用户指定的是输入数量（exact-in）还是输出数量（exact-out）
+    pub amount_specified_is_input: bool,
+    /// 用户的滑点边界：exact-in时为最少输出，exact-out时为最多输入，0表示未知
+    pub other_amount_threshold: u64,
+    /// 用户支付输入代币的账户
+    pub user_source: Option<String>,
+    /// 用户接收输出代币的账户
+    pub user_destination: Option<String>,
+    /// 池子接收输入代币的金库
+    pub vault_in: Option<String>,
+    /// 池子支付输出代币的金库
+    pub vault_out: Option<String>,
+    /// 集中流动性池的价格限制（sqrt price，Q64.64），0或None表示不限制
+    pub sqrt_price_limit: Option<u128>,
+    /// 从程序日志中解码出的对应swap事件（提供精确成交数量）
+    pub event: Option<SwapEvent>,
+}
+
+impl SwapInstructionData {
+    /// 实际成交相对于用户滑点边界的剩余空间（比例，0表示恰好成交在边界上）
+    ///
+    /// 仅在日志事件给出实际成交数量时有意义。
+    pub fn slippage_headroom(&self) -> Option<f64> {
+        self.event.as_ref()?;
+        if self.other_amount_threshold == 0 {
+            return None;
+        }
+        
+        if self.amount_specified_is_input {
+            // exact-in: 实际输出高出最少输出的比例
+            if self.amount_out == 0 {
+                return None;
+            }
+            Some(self.amount_out.saturating_sub(self.other_amount_threshold) as f64 / self.amount_out as f64)
+        } else {
+            // exact-out: 实际输入低于最多输入的比例
+            Some(self.other_amount_threshold.saturating_sub(self.amount_in) as f64
+                / self.other_amount_threshold as f64)
+        }
+    }
 }
 
 /// 交易指令解析汇总
@@ -130,23 +191,93 @@ pub struct FrontrunDetails {
     pub account_intersection: Vec<String>,
 }
 
+/// 执行失败的攻击尝试（与目标交易操作同一池子但执行失败的DEX交易）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FailedAttackAttempt {
+    pub signature: String,
+    pub slot: u64,
+    /// 在区块中的原始位置
+    pub block_index: Option<usize>,
+    pub is_before_target: bool,
+    /// `meta.err`的JSON文本
+    pub error: String,
+    pub account_intersection: Vec<String>,
+}
+
+/// 整块扫描中的一笔交易
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScannedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    /// 在区块中的原始位置
+    pub block_index: Option<usize>,
+    /// 费用支付者（第一个签名者）
+    pub signer: String,
+}
+
+impl ScannedTransaction {
+    fn from_transaction(tx: &Transaction) -> Self {
+        Self {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_index: tx.block_index,
+            signer: fee_payer(tx).to_string(),
+        }
+    }
+}
+
+/// 三明治攻击中的受害交易
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SandwichVictim {
+    pub transaction: ScannedTransaction,
+    pub loss: Option<UserLoss>,
+    /// 区块中与该受害交易共享账户、执行失败的DEX交易
+    pub failed_attempts: Vec<FailedAttackAttempt>,
+}
+
+/// 整块扫描发现的三明治攻击（同一对前置/后置交易可能夹住多笔受害交易）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlockSandwich {
+    /// 前置和后置交易的共同签名者
+    pub attacker: String,
+    pub front: ScannedTransaction,
+    pub back: ScannedTransaction,
+    pub victims: Vec<SandwichVictim>,
+    /// 被攻击的池子地址
+    pub pools: Vec<String>,
+    pub account_intersection: Vec<String>,
+}
+
+/// 交易的费用支付者
+pub fn fee_payer(tx: &Transaction) -> &str {
+    tx.transaction.message.account_keys.first().map_or("", String::as_str)
+}
+
+/// 束包只会落在同一个slot中，跨slot的交易序列需按slot截取
+fn same_slot(transactions: &[Transaction], slot: u64) -> Vec<Transaction> {
+    transactions.iter().filter(|tx| tx.slot == slot).cloned().collect()
+}
+
 // 程序ID常量定义
-mod program_ids {
+pub(crate) mod program_ids {
     pub const RAYDIUM_AMM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
     pub const RAYDIUM_CLMM: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUQzGdR1qJRXgKhpNzc";
     pub const ORCA_WHIRLPOOLS: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
     pub const ORCA_V1: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
-    pub const SERUM_DEX: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
     pub const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
     pub const PUMP_FUN: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+    pub const RAYDIUM_CPMM: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C";
+    pub const METEORA_DLMM: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9t1Rqsv8x";
+    pub const METEORA_DYNAMIC_AMM: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
+    pub const PHOENIX: &str = "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY";
+    pub const OPENBOOK_V2: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
 
     pub const SYSTEM: &str = "11111111111111111111111111111111";
     pub const MEMO: &str = "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDgQdddcxFr";
-    pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 }
 
 // 常用代币地址和信息
-mod token_info {
+pub(crate) mod token_info {
     pub const WSOL: &str = "So11111111111111111111111111111111111111112";
     pub const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
     pub const USDT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
@@ -199,7 +330,20 @@ const ALLOWED_PROGRAMS_FOR_SIMPLE_TRANSFER: [&str; 2] = [SYSTEM, MEMO];
 impl MevDetector {
     /// 创建新的MEV检测器实例
     pub fn new(config: MevDetectionConfig, language: Language) -> Self {
-        Self { config, locale: Locale::new(language) }
+        Self::with_decoders(config, language, DecoderRegistry::default())
+    }
+
+    /// 使用指定的解码器注册表创建检测器，用于接入自定义DEX解码器
+    pub fn with_decoders(config: MevDetectionConfig, language: Language, decoders: DecoderRegistry) -> Self {
+        Self { config, locale: Locale::new(language), decoders }
+    }
+
+    /// 目标交易前后搜索的交易数（配置为0表示不限制）
+    fn window_transactions(&self) -> usize {
+        match self.config.window_transactions {
+            0 => usize::MAX,
+            n => n,
+        }
     }
 
     /// 检查交易是否为简单的转账
@@ -234,7 +378,7 @@ impl MevDetector {
             {
                 info!("{}", self.locale.jito_tip_found_before());
                 let bundle_end = (i + 5).min(block_transactions.len());
-                let bundle_transactions = block_transactions[i..bundle_end].to_vec();
+                let bundle_transactions = same_slot(&block_transactions[i..bundle_end], tx.slot);
                 return Some((i, tip_account, tip_amount, true, bundle_transactions));
             }
         }
@@ -246,7 +390,7 @@ impl MevDetector {
             {
                 info!("{}", self.locale.jito_tip_found_after());
                 let bundle_start = i.saturating_sub(4);
-                let bundle_transactions = block_transactions[bundle_start..=i].to_vec();
+                let bundle_transactions = same_slot(&block_transactions[bundle_start..=i], tx.slot);
                 return Some((i, tip_account, tip_amount, false, bundle_transactions));
             }
         }
@@ -324,66 +468,6 @@ impl MevDetector {
         None
     }
 
-    /// 在本地交易中找到对应的束包信息
-    fn find_local_bundle_transactions(
-        &self,
-        block_transactions: &[Transaction],
-        target_index: usize,
-        bundle_info: &JitoBundleInfo,
-    ) -> Option<(usize, String, u64, bool, Vec<Transaction>, Option<JitoBundleInfo>)> {
-        // 在目标交易附近寻找可能的小费交易
-        let search_range = 10; // 搜索范围扩大到前后10个交易
-        let start_index = target_index.saturating_sub(search_range);
-        let end_index = (target_index + search_range).min(block_transactions.len());
-        
-        for i in start_index..end_index {
-            if i == target_index {
-                continue; // 跳过目标交易自身
-            }
-            
-            let tx = &block_transactions[i];
-            
-            // 检查是否包含Jito小费账户
-            if let Some((tip_account, _)) = self.check_single_transaction_for_jito_tip(tx) {
-                let is_before_target = i < target_index;
-                
-                // 构建束包交易列表
-                let bundle_transactions = if is_before_target {
-                    let bundle_end = (i + 5).min(block_transactions.len());
-                    block_transactions[i..bundle_end].to_vec()
-                } else {
-                    let bundle_start = i.saturating_sub(4);
-                    block_transactions[bundle_start..=i].to_vec()
-                };
-                
-                info!("通过API找到束包，本地验证成功");
-                return Some((
-                    i,
-                    tip_account,
-                    bundle_info.landed_tip_lamports,
-                    is_before_target,
-                    bundle_transactions,
-                    None, // 这里暂时为None，稍后会被设置
-                ));
-            }
-        }
-        
-        // 如果没有找到对应的小费交易，但API确认有束包，创建一个虚拟的束包结构
-        info!("API确认有束包但本地未找到小费交易，创建虚拟束包结构");
-        let bundle_start = target_index.saturating_sub(2);
-        let bundle_end = (target_index + 3).min(block_transactions.len());
-        let bundle_transactions = block_transactions[bundle_start..bundle_end].to_vec();
-        
-        Some((
-            target_index,
-            "API_CONFIRMED".to_string(), // 使用特殊标识表示这是API确认的束包
-            bundle_info.landed_tip_lamports,
-            false, // 假设小费在目标交易之后
-            bundle_transactions,
-            None, // 这里暂时为None，稍后会被设置
-        ))
-    }
-
     /// 分析目标交易在束包中的位置
     pub fn analyze_bundle_position(
         &self,
@@ -423,11 +507,12 @@ impl MevDetector {
     ) -> Vec<Transaction> {
         let mut transactions = Vec::new();
         
-        for signature in &bundle_info.transactions {
-            if let Ok(tx) = client.get_transaction(signature).await {
-                transactions.push(tx);
-            } else {
-                debug!("无法获取束包交易: {}", signature);
+        // 一次批量请求获取束包内所有交易
+        let results = client.get_transactions(&bundle_info.transactions).await;
+        for (signature, result) in bundle_info.transactions.iter().zip(results) {
+            match result {
+                Ok(tx) => transactions.push(tx),
+                Err(e) => debug!("无法获取束包交易 {}: {}", signature, e),
             }
         }
         
@@ -435,84 +520,15 @@ impl MevDetector {
         transactions
     }
 
-    /// 分析交易中的代币交换对
-    async fn analyze_swap_pair(
-        &self,
-        client: &crate::client::SolanaClient,
-        target_tx_sig: &str,
-        front_tx_sig: &str,
-        back_tx_sig: &str,
-    ) -> Option<(String, String, f64)> { // (token_a_symbol, token_b_symbol, exchange_rate)
-        // 尝试获取交易的余额变化数据来分析交换比率
-        if let (Ok(target_tx), Ok(front_tx), Ok(back_tx)) = (
-            client.get_transaction_with_balance_changes(target_tx_sig).await,
-            client.get_transaction_with_balance_changes(front_tx_sig).await,
-            client.get_transaction_with_balance_changes(back_tx_sig).await,
-        ) {
-            // 分析用户交易的代币变化
-            if let Some(meta) = &target_tx.meta {
-                let mut token_changes = Vec::new();
-                
-                // 分析Token余额变化
-                for post_token in &meta.post_token_balances {
-                    if let Some(pre_token) = meta.pre_token_balances.iter()
-                        .find(|pre| pre.account_index == post_token.account_index && pre.mint == post_token.mint) {
-                        
-                        let pre_amount = pre_token.ui_token_amount.ui_amount.unwrap_or(0.0);
-                        let post_amount = post_token.ui_token_amount.ui_amount.unwrap_or(0.0);
-                        let change = post_amount - pre_amount;
-                        
-                        if change.abs() > 0.000001 { // 有变化的代币
-                            token_changes.push((
-                                post_token.mint.clone(),
-                                get_token_symbol(&post_token.mint).to_string(),
-                                change,
-                            ));
-                        }
-                    }
-                }
-                
-                // 分析SOL余额变化
-                let mut sol_change = 0i64;
-                for (&pre_balance, &post_balance) in meta.pre_balances.iter()
-                    .zip(meta.post_balances.iter()) {
-                    sol_change += post_balance as i64 - pre_balance as i64;
-                }
-                
-                if sol_change.abs() > 1000 { // SOL有变化
-                    token_changes.push((
-                        token_info::WSOL.to_string(),
-                        "SOL".to_string(),
-                        sol_change as f64 / 1_000_000_000.0,
-                    ));
-                }
-                
-                // 找到变化最大的两个代币（一个增加，一个减少）
-                if token_changes.len() >= 2 {
-                    token_changes.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
-                    
-                    let token_out = &token_changes[0]; // 减少的代币（用户卖出）
-                    let token_in = &token_changes[token_changes.len() - 1]; // 增加的代币（用户买入）
-                    
-                    if token_out.2 < 0.0 && token_in.2 > 0.0 {
-                        let exchange_rate = token_out.2.abs() / token_in.2;
-                        debug!("检测到交换对: {} -> {}, 汇率: {:.9}", 
-                               token_out.1, token_in.1, exchange_rate);
-                        return Some((token_out.1.clone(), token_in.1.clone(), exchange_rate));
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-
     /// 检查单个交易是否包含Jito小费
     fn check_single_transaction_for_jito_tip(&self, tx: &Transaction) -> Option<(String, u64)> {
-        let jito_tip_indices: Vec<(usize, String)> = tx
-            .transaction
-            .message
-            .account_keys
+        // 失败的交易不会转出小费
+        if tx.is_failed() {
+            return None;
+        }
+
+        let account_keys = tx.account_keys();
+        let jito_tip_indices: Vec<(usize, String)> = account_keys
             .iter()
             .enumerate()
             .filter(|(_, account)| JITO_TIP_ACCOUNTS.contains(&account.as_str()))
@@ -526,12 +542,10 @@ impl MevDetector {
             return None;
         }
 
-        for instruction in &tx.transaction.message.instructions {
-            let program_id = tx
-                .transaction
-                .message
-                .account_keys
-                .get(instruction.program_id_index as usize)?;
+        // 遍历完整指令树，机器人程序通过CPI支付的小费也能被识别
+        for node in tx.instruction_tree() {
+            let instruction = node.instruction;
+            let program_id = account_keys.get(instruction.program_id_index as usize)?;
 
             for &account_index in &instruction.accounts {
                 for &(jito_index, ref jito_address) in &jito_tip_indices {
@@ -584,85 +598,20 @@ impl MevDetector {
         let target_index = transactions
             .iter()
             .position(|tx| tx.signature == target_signature)?;
-        let target_tx = &transactions[target_index];
-
-        if !self.is_dex_transaction(target_tx) {
-            return None;
-        }
-
-        let target_accounts = self.extract_filtered_accounts(target_tx);
-        if target_accounts.is_empty() {
-            return None;
-        }
-
-        debug!("Target transaction filtered accounts: {}", target_accounts.len());
-
-        let mut front_candidates = Vec::new();
-        for i in 0..target_index.min(2) {
-            let front_tx = &transactions[target_index.saturating_sub(i + 1)];
-            if !self.is_dex_transaction(front_tx) {
-                continue;
-            }
-
-            let front_accounts = self.extract_filtered_accounts(front_tx);
-            let front_intersection: Vec<String> = target_accounts
-                .intersection(&front_accounts)
-                .cloned()
-                .collect();
-
-            if !front_intersection.is_empty() {
-                front_candidates.push((front_tx, front_intersection));
-            }
-        }
 
-        let mut back_candidates = Vec::new();
-        for i in 0..2.min(transactions.len() - target_index - 1) {
-            let back_tx = &transactions[target_index + i + 1];
-            if !self.is_dex_transaction(back_tx) {
-                continue;
-            }
-
-            let back_accounts = self.extract_filtered_accounts(back_tx);
-            let back_intersection: Vec<String> = target_accounts
-                .intersection(&back_accounts)
-                .cloned()
-                .collect();
-
-            if !back_intersection.is_empty() {
-                back_candidates.push((back_tx, back_intersection));
-            }
-        }
-
-        for (front_tx, front_intersection) in &front_candidates {
-            for (back_tx, back_intersection) in &back_candidates {
-                let intersection_similarity =
-                    self.calculate_intersection_similarity(front_intersection, back_intersection);
-
-                if intersection_similarity >= self.config.similarity_threshold {
-                    info!(
-                        "{} {:.1}%",
-                        self.locale.sandwich_pattern_detected(),
-                        intersection_similarity * 100.0
-                    );
-
-                    let mut combined_intersection = front_intersection.clone();
-                    for account in back_intersection {
-                        if !combined_intersection.contains(account) {
-                            combined_intersection.push(account.clone());
-                        }
-                    }
-
-                    return Some(SandwichDetails {
-                        front_tx: front_tx.signature.clone(),
-                        back_tx: back_tx.signature.clone(),
-                        account_intersection: combined_intersection,
-                        user_loss: None, // 不再在此处计算用户损失，只在精确分析中计算
-                    });
-                }
-            }
-        }
+        let accounts = self.sandwich_candidate_accounts(transactions);
+        let legs = self.find_sandwich_legs(transactions, &accounts, target_index)?;
+        info!(
+            "{} {:.1}%",
+            self.locale.sandwich_pattern_detected(),
+            legs.similarity * 100.0
+        );
 
-        None
+        Some(SandwichDetails {
+            front_tx: transactions[legs.front].signature.clone(),
+            back_tx: transactions[legs.back].signature.clone(),
+            account_intersection: legs.account_intersection,
+        })
     }
 
     /// 检测交易列表中是否存在抢跑攻击
@@ -676,7 +625,7 @@ impl MevDetector {
             .position(|tx| tx.signature == target_signature)?;
         let target_tx = &transactions[target_index];
 
-        if !self.is_dex_transaction(target_tx) {
+        if target_tx.is_failed() || !self.is_dex_transaction(target_tx) {
             return None;
         }
 
@@ -693,7 +642,7 @@ impl MevDetector {
         for i in (0..target_index).rev() {
             let potential_frontrun = &transactions[i];
 
-            if !self.is_dex_transaction(potential_frontrun) {
+            if potential_frontrun.is_failed() || !self.is_dex_transaction(potential_frontrun) {
                 continue;
             }
 
@@ -717,31 +666,255 @@ impl MevDetector {
         None
     }
 
+    /// 找出目标交易附近执行失败的攻击尝试
+    ///
+    /// 失败的交易不参与三明治/抢跑检测，这里单独列出与目标交易共享账户的失败DEX交易，
+    /// 用于区分机器人的无效尝试和真正成功的价值提取。
+    pub fn detect_failed_attack_attempts(
+        &self,
+        transactions: &[Transaction],
+        target_signature: &str,
+    ) -> Vec<FailedAttackAttempt> {
+        let Some(target_index) = transactions
+            .iter()
+            .position(|tx| tx.signature == target_signature)
+        else {
+            return Vec::new();
+        };
+
+        let target_accounts = self.extract_filtered_accounts(&transactions[target_index]);
+        if target_accounts.is_empty() {
+            return Vec::new();
+        }
+
+        transactions
+            .iter()
+            .enumerate()
+            .filter(|&(i, tx)| i != target_index && tx.is_failed() && self.is_dex_transaction(tx))
+            .filter_map(|(i, tx)| {
+                let accounts = self.extract_filtered_accounts(tx);
+                let intersection: Vec<String> = target_accounts
+                    .intersection(&accounts)
+                    .cloned()
+                    .collect();
+                if intersection.is_empty() {
+                    return None;
+                }
+
+                Some(FailedAttackAttempt {
+                    signature: tx.signature.clone(),
+                    slot: tx.slot,
+                    block_index: tx.block_index,
+                    is_before_target: i < target_index,
+                    error: tx.execution_error().map(|e| e.to_string()).unwrap_or_default(),
+                    account_intersection: intersection,
+                })
+            })
+            .collect()
+    }
+
+    /// 扫描整个区块中的所有三明治攻击
+    ///
+    /// 区块只获取一次。每笔成功的DEX交易都作为候选受害交易，在配置的窗口内寻找与其共享账户、
+    /// 由同一签名者发起的前置和后置交易；同一对前置/后置交易夹住的多笔受害交易合并为一次攻击。
+    /// 每笔受害交易附带区块中与其共享账户的失败攻击尝试。
+    pub async fn scan_block(
+        &self,
+        client: &crate::client::SolanaClient,
+        slot: u64,
+    ) -> Result<Vec<BlockSandwich>, ClientError> {
+        let transactions = client.get_full_block(slot).await?;
+
+        let accounts = self.sandwich_candidate_accounts(&transactions);
+        debug!("区块{}中有 {} 笔DEX交易", slot, accounts.iter().flatten().count());
+
+        // (前置, 后置, 受害交易, 账户交集)
+        let mut found: Vec<(usize, usize, Vec<usize>, Vec<String>)> = Vec::new();
+        for victim in 0..transactions.len() {
+            let Some(legs) = self.find_sandwich_legs(&transactions, &accounts, victim) else {
+                continue;
+            };
+            match found.iter_mut().find(|(f, b, _, _)| *f == legs.front && *b == legs.back) {
+                Some((_, _, victims, combined)) => {
+                    victims.push(victim);
+                    for account in legs.account_intersection {
+                        if !combined.contains(&account) {
+                            combined.push(account);
+                        }
+                    }
+                }
+                None => found.push((legs.front, legs.back, vec![victim], legs.account_intersection)),
+            }
+        }
+
+        let mut sandwiches = Vec::new();
+        for (front, back, victim_indices, account_intersection) in found {
+            let front_tx = &transactions[front];
+            let back_tx = &transactions[back];
+
+            let mut victims = Vec::new();
+            for &victim in &victim_indices {
+                let victim_tx = &transactions[victim];
+                let loss = match self
+                    .calculate_sandwich_loss(client, &front_tx.signature, &victim_tx.signature, &back_tx.signature)
+                    .await
+                {
+                    Ok(loss) => loss,
+                    Err(e) => {
+                        debug!("无法计算受害交易{}的损失: {}", victim_tx.signature, e);
+                        None
+                    }
+                };
+                victims.push(SandwichVictim {
+                    transaction: ScannedTransaction::from_transaction(victim_tx),
+                    loss,
+                    failed_attempts: self.detect_failed_attack_attempts(&transactions, &victim_tx.signature),
+                });
+            }
+
+            // 前置交易swap的池子中，受害交易也用到的那些
+            let victim_accounts: HashSet<String> = victim_indices
+                .iter()
+                .flat_map(|&victim| transactions[victim].account_keys())
+                .collect();
+            let mut pools: Vec<String> = Vec::new();
+            for swap in self.parse_transaction_instructions(front_tx).swap_instructions {
+                if victim_accounts.contains(&swap.pool_address) && !pools.contains(&swap.pool_address) {
+                    pools.push(swap.pool_address);
+                }
+            }
+
+            sandwiches.push(BlockSandwich {
+                attacker: fee_payer(front_tx).to_string(),
+                front: ScannedTransaction::from_transaction(front_tx),
+                back: ScannedTransaction::from_transaction(back_tx),
+                victims,
+                pools,
+                account_intersection,
+            });
+        }
+
+        info!("区块{}中发现 {} 次三明治攻击", slot, sandwiches.len());
+        Ok(sandwiches)
+    }
+
+    /// 每笔交易参与三明治检测的过滤后账户，只有成功执行的DEX交易参与检测（其余为None）
+    fn sandwich_candidate_accounts(&self, transactions: &[Transaction]) -> Vec<Option<HashSet<String>>> {
+        transactions
+            .iter()
+            .map(|tx| {
+                if tx.is_failed() || !self.is_dex_transaction(tx) {
+                    return None;
+                }
+                Some(self.extract_filtered_accounts(tx)).filter(|accounts| !accounts.is_empty())
+            })
+            .collect()
+    }
+
+    /// 为受害交易寻找前置和后置交易：窗口内与其共享账户、由同一签名者（且不是受害者本人）发起，
+    /// 前后账户交集相似度达到阈值。单笔交易分析和整区块扫描共用这一规则。
+    fn find_sandwich_legs(
+        &self,
+        transactions: &[Transaction],
+        accounts: &[Option<HashSet<String>>],
+        victim: usize,
+    ) -> Option<SandwichLegs> {
+        let victim_accounts = accounts[victim].as_ref()?;
+        let victim_signer = fee_payer(&transactions[victim]);
+        let window = self.window_transactions();
+
+        let legs = |range: Vec<usize>| -> Vec<(usize, Vec<String>)> {
+            range
+                .into_iter()
+                .filter(|&i| fee_payer(&transactions[i]) != victim_signer)
+                .filter_map(|i| {
+                    let intersection: Vec<String> = victim_accounts
+                        .intersection(accounts[i].as_ref()?)
+                        .cloned()
+                        .collect();
+                    (!intersection.is_empty()).then_some((i, intersection))
+                })
+                .collect()
+        };
+        // 由近及远
+        let fronts = legs((victim.saturating_sub(window)..victim).rev().collect());
+        let backs = legs((victim + 1..victim.saturating_add(window).saturating_add(1).min(transactions.len())).collect());
+
+        for (front, front_intersection) in &fronts {
+            for (back, back_intersection) in &backs {
+                if fee_payer(&transactions[*front]) != fee_payer(&transactions[*back]) {
+                    continue;
+                }
+                let similarity = self.calculate_intersection_similarity(front_intersection, back_intersection);
+                if similarity < self.config.similarity_threshold {
+                    continue;
+                }
+
+                let mut account_intersection = front_intersection.clone();
+                for account in back_intersection {
+                    if !account_intersection.contains(account) {
+                        account_intersection.push(account.clone());
+                    }
+                }
+                return Some(SandwichLegs {
+                    front: *front,
+                    back: *back,
+                    similarity,
+                    account_intersection,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// 计算三明治攻击中受害交易的损失 - 依次尝试各计算方法
+    ///
+    /// 联合曲线和余额变化方法获取交易失败时回退到下一种方法；
+    /// 若节点已裁剪相关历史，所有方法都无法获取交易，直接返回错误。
+    pub async fn calculate_sandwich_loss(
+        &self,
+        client: &crate::client::SolanaClient,
+        front_tx_sig: &str,
+        target_tx_sig: &str,
+        back_tx_sig: &str,
+    ) -> Result<Option<UserLoss>, ClientError> {
+        // 方法1: Pump.fun代币直接使用联合曲线状态精确计算
+        match self.calculate_bonding_curve_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await {
+            Ok(Some(loss)) => return Ok(Some(loss)),
+            Ok(None) => {}
+            Err(e) if e.is_history_unavailable() => return Err(e),
+            Err(e) => debug!("联合曲线分析无法获取交易: {}", e),
+        }
+
+        // 方法2: 使用余额变化分析
+        match self.calculate_precise_sandwich_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await {
+            Ok(Some(loss)) => return Ok(Some(loss)),
+            Ok(None) => {}
+            Err(e) if e.is_history_unavailable() => return Err(e),
+            Err(e) => debug!("无法获取完整的余额变化数据（{}），回退到指令解析方法", e),
+        }
+
+        // 方法3: 回退到指令解析分析
+        self.calculate_instruction_based_loss(client, front_tx_sig, target_tx_sig, back_tx_sig).await
+    }
+
     /// 提取交易中的过滤后账户
     fn extract_filtered_accounts(&self, tx: &Transaction) -> HashSet<String> {
         let mut filtered_accounts = HashSet::new();
+        let account_keys = tx.account_keys();
 
         for instruction in &tx.transaction.message.instructions {
-            if let Some(program_id) = tx
-                .transaction
-                .message
-                .account_keys
-                .get(instruction.program_id_index as usize)
-            {
+            if let Some(program_id) = account_keys.get(instruction.program_id_index as usize) {
                 if program_id == SYSTEM {
-                    if self.is_small_transfer_instruction(
-                        instruction,
-                        &tx.transaction.message.account_keys,
-                    ) {
+                    if self.is_small_transfer_instruction(instruction, &account_keys) {
                         continue;
                     }
                 }
 
                 for &acc_index in &instruction.accounts {
-                    if let Some(account) =
-                        tx.transaction.message.account_keys.get(acc_index as usize)
-                    {
-                        if !self.is_account_writable(acc_index as usize, &tx.transaction.message) {
+                    if let Some(account) = account_keys.get(acc_index as usize) {
+                        if !tx.is_account_writable(acc_index as usize) {
                             continue;
                         }
 
@@ -755,6 +928,9 @@ impl MevDetector {
             }
         }
 
+        // 解码器识别出的池子/金库等共享账户（包括仅出现在CPI中的swap）
+        filtered_accounts.extend(self.pool_accounts(tx));
+
         filtered_accounts.retain(|account| {
             account.len() <= 44 &&
             account.chars().all(|c| "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz".contains(c))
@@ -763,26 +939,6 @@ impl MevDetector {
         filtered_accounts
     }
 
-    /// 判断指定索引的账户是否可写
-    fn is_account_writable(&self, account_index: usize, message: &crate::client::Message) -> bool {
-        if let Some(header) = &message.header {
-            let num_required_signatures = header.num_required_signatures as usize;
-            let num_readonly_signed_accounts = header.num_readonly_signed_accounts as usize;
-            let num_readonly_unsigned_accounts = header.num_readonly_unsigned_accounts as usize;
-
-            if account_index < num_required_signatures {
-                account_index < (num_required_signatures - num_readonly_signed_accounts)
-            } else {
-                let unsigned_start = num_required_signatures;
-                let readonly_unsigned_start =
-                    message.account_keys.len() - num_readonly_unsigned_accounts;
-                account_index >= unsigned_start && account_index < readonly_unsigned_start
-            }
-        } else {
-            true
-        }
-    }
-
     /// 检查指令是否为小额转账
     fn is_small_transfer_instruction(
         &self,
@@ -839,24 +995,11 @@ impl MevDetector {
 
     /// 检查交易是否为DEX交易
     pub fn is_dex_transaction(&self, tx: &Transaction) -> bool {
-        const DEX_PROGRAMS: [&str; 7] = [
-            RAYDIUM_AMM,
-            RAYDIUM_CLMM,
-            ORCA_WHIRLPOOLS,
-            ORCA_V1,
-            SERUM_DEX,
-            JUPITER,
-            PUMP_FUN,
-        ];
-
-        let has_known_dex = tx.transaction.message.instructions.iter().any(|inst| {
-            if let Some(program_id) = tx
-                .transaction
-                .message
-                .account_keys
-                .get(inst.program_id_index as usize)
-            {
-                DEX_PROGRAMS.contains(&program_id.as_str())
+        // 包含CPI内部指令：聚合器或机器人合约可能通过CPI调用DEX
+        let account_keys = tx.account_keys();
+        let has_known_dex = tx.instruction_tree().iter().any(|node| {
+            if let Some(program_id) = account_keys.get(node.instruction.program_id_index as usize) {
+                self.decoders.is_dex_program(program_id)
             } else {
                 false
             }
@@ -871,7 +1014,7 @@ impl MevDetector {
 
     /// 通过账户特征判断是否可能是swap交易
     fn is_likely_swap_transaction(&self, tx: &Transaction) -> bool {
-        let account_count = tx.transaction.message.account_keys.len();
+        let account_count = tx.account_keys().len();
 
         let has_multiple_accounts = account_count >= 6; // 默认最少6个账户的swap交易
 
@@ -896,9 +1039,7 @@ impl MevDetector {
     /// 检查是否有token账户的特征
     fn has_token_account_patterns(&self, tx: &Transaction) -> bool {
         let typical_token_account_count = tx
-            .transaction
-            .message
-            .account_keys
+            .account_keys()
             .iter()
             .filter(|key| key.len() == 44)
             .count();
@@ -916,33 +1057,30 @@ impl MevDetector {
         front_tx_sig: &str,
         target_tx_sig: &str,
         back_tx_sig: &str,
-    ) -> Option<UserLoss> {
+    ) -> Result<Option<UserLoss>, ClientError> {
         debug!("开始尝试使用余额变化进行精确损失计算");
         
-        // 尝试获取三个交易的详细余额变化信息
-        let front_tx_result = client.get_transaction_with_balance_changes(front_tx_sig).await;
-        let target_tx_result = client.get_transaction_with_balance_changes(target_tx_sig).await;
-        let back_tx_result = client.get_transaction_with_balance_changes(back_tx_sig).await;
-        
-        // 检查是否所有交易都能获取到余额变化数据
-        if let (Ok(front_tx), Ok(target_tx), Ok(back_tx)) = 
-            (front_tx_result, target_tx_result, back_tx_result) {
-            
-            debug!("成功获取所有交易的余额变化数据，使用精确分析");
-            return self.perform_precise_analysis(&client, &front_tx, &target_tx, &back_tx, target_tx_sig, front_tx_sig, back_tx_sig).await;
-        }
+        // 批量获取三个交易的详细余额变化信息，任一失败都由调用方决定是否回退
+        let transactions = client
+            .get_transactions(&[front_tx_sig, target_tx_sig, back_tx_sig])
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let [front_tx, target_tx, back_tx] = transactions.as_slice() else {
+            return Ok(None);
+        };
         
-        debug!("无法获取完整的余额变化数据（可能是历史交易），回退到改进的估算方法");
-        None
+        debug!("成功获取所有交易的余额变化数据，使用精确分析");
+        Ok(self.perform_precise_analysis(client, front_tx, target_tx, back_tx, target_tx_sig, front_tx_sig, back_tx_sig).await)
     }
     
     /// 执行精确的余额变化分析
     async fn perform_precise_analysis(
         &self,
         client: &crate::client::SolanaClient,
-        front_tx: &TransactionWithBalanceChanges,
-        target_tx: &TransactionWithBalanceChanges,
-        back_tx: &TransactionWithBalanceChanges,
+        front_tx: &Transaction,
+        target_tx: &Transaction,
+        back_tx: &Transaction,
         target_tx_sig: &str,
         front_tx_sig: &str,
         back_tx_sig: &str,
@@ -1093,8 +1231,8 @@ impl MevDetector {
         };
         
         // 如果主要损失是SOL，需要重新创建包含SOL损失的token_losses
-        let final_token_losses = if token_losses.is_empty() || 
-            token_losses.iter().all(|t| t.token_symbol != "SOL") {
+        // （有token损失时estimated_user_loss以该token为单位，不能作为SOL损失）
+        let final_token_losses = if token_losses.is_empty() {
             self.create_precise_token_losses(&front_inflow, estimated_user_loss)
         } else {
             token_losses
@@ -1134,7 +1272,7 @@ impl MevDetector {
     }
     
     /// 分析交易的精确流入（基于余额变化）
-    fn analyze_precise_inflow(&self, tx: &TransactionWithBalanceChanges) -> PreciseInflowAnalysis {
+    fn analyze_precise_inflow(&self, tx: &Transaction) -> PreciseInflowAnalysis {
         let mut total_sol_inflow = 0u64;
         let mut token_inflows = Vec::new();
         
@@ -1186,7 +1324,7 @@ impl MevDetector {
     }
     
     /// 分析交易的精确流出（基于余额变化）
-    fn analyze_precise_outflow(&self, tx: &TransactionWithBalanceChanges) -> PreciseOutflowAnalysis {
+    fn analyze_precise_outflow(&self, tx: &Transaction) -> PreciseOutflowAnalysis {
         let mut total_sol_outflow = 0u64;
         
         if let Some(meta) = &tx.meta {
@@ -1209,7 +1347,7 @@ impl MevDetector {
     }
     
     /// 分析交易的精确token流出（基于余额变化）
-    fn analyze_precise_token_outflow(&self, tx: &TransactionWithBalanceChanges) -> Vec<TokenFlowDetail> {
+    fn analyze_precise_token_outflow(&self, tx: &Transaction) -> Vec<TokenFlowDetail> {
         let mut token_outflows = Vec::new();
         
         if let Some(meta) = &tx.meta {
@@ -1246,7 +1384,7 @@ impl MevDetector {
     }
     
     /// 分析交易的精确价值（基于余额变化）
-    fn analyze_precise_trade_value(&self, tx: &TransactionWithBalanceChanges) -> u64 {
+    fn analyze_precise_trade_value(&self, tx: &Transaction) -> u64 {
         let mut total_value = 0u64;
         
         if let Some(meta) = &tx.meta {
@@ -1401,28 +1539,70 @@ impl MevDetector {
         let mut total_sol_amount = 0u64;
         let mut involved_tokens = Vec::new();
         
-        debug!("开始解析交易指令，共{}个指令", tx.transaction.message.instructions.len());
+        debug!("开始解析交易指令，共{}个顶层指令，{}组内部指令",
+               tx.transaction.message.instructions.len(), tx.inner_instructions().len());
         
-        for (idx, instruction) in tx.transaction.message.instructions.iter().enumerate() {
-            if let Some(program_id) = tx.transaction.message.account_keys.get(instruction.program_id_index as usize) {
-                debug!("指令{}: program_id = {}", idx, program_id);
+        let account_keys = tx.account_keys();
+        let mut decoded: Vec<(usize, bool, SwapInstructionData)> = Vec::new();
+        let mut cpi_events: Vec<SwapEvent> = Vec::new();
+        
+        // 遍历完整指令树，聚合器/机器人合约通过CPI调用的DEX指令同样需要解析
+        for node in tx.instruction_tree() {
+            let instruction = node.instruction;
+            if let Some(program_id) = account_keys.get(instruction.program_id_index as usize) {
+                debug!("指令{}{}: program_id = {}", node.top_level_index,
+                       node.inner_index.map(|i| format!(".{}", i)).unwrap_or_default(), program_id);
                 
-                if let Some(swap_data) = self.parse_swap_instruction(instruction, &tx.transaction.message.account_keys, program_id) {
-                    debug!("成功解析swap指令: {:?}", swap_data);
-                    total_sol_amount += swap_data.amount_in;
-                    
-                    if !involved_tokens.contains(&swap_data.token_in) {
-                        involved_tokens.push(swap_data.token_in.clone());
-                    }
-                    if !involved_tokens.contains(&swap_data.token_out) {
-                        involved_tokens.push(swap_data.token_out.clone());
+                let swaps = self.parse_swap_instruction(tx, instruction, &account_keys, program_id);
+                if !swaps.is_empty() {
+                    decoded.extend(swaps.into_iter().map(|swap_data| (node.top_level_index, node.is_inner(), swap_data)));
+                } else if node.is_inner() {
+                    // Anchor emit_cpi!事件以自调用内部指令的形式出现（如Pump.fun的TradeEvent）
+                    if let Some(event) = self.decoders.decode_cpi_event(program_id, &instruction.data) {
+                        cpi_events.push(event);
                     }
-                    
-                    swap_instructions.push(swap_data);
                 }
             }
         }
         
+        // 聚合器指令本身只能粗略解析；若其CPI中已解析出具体DEX的swap，则以内部指令为准
+        let routed_indices: HashSet<usize> = decoded.iter()
+            .filter(|(_, is_inner, swap)| *is_inner && !self.is_aggregator_swap(swap))
+            .map(|(top_level_index, _, _)| *top_level_index)
+            .collect();
+        
+        // 程序日志和emit_cpi事件中的swap事件按执行顺序与解析出的swap指令一一对应
+        let mut events: Vec<Option<SwapEvent>> = self.decoders.decode_swap_events(tx.log_messages())
+            .into_iter()
+            .chain(cpi_events)
+            .map(Some)
+            .collect();
+        debug!("从程序日志和CPI事件中解码到{}个swap事件", events.len());
+        
+        for (top_level_index, _, mut swap_data) in decoded {
+            if self.is_aggregator_swap(&swap_data) && routed_indices.contains(&top_level_index) {
+                continue;
+            }
+            
+            if let Some(event) = self.take_matching_event(&mut events, &swap_data) {
+                self.apply_swap_event(&mut swap_data, event);
+            } else {
+                self.settle_from_token_balances(tx, &account_keys, &mut swap_data);
+            }
+            
+            debug!("成功解析{} swap指令: {:?}", swap_data.venue, swap_data);
+            total_sol_amount += swap_data.amount_in;
+            
+            if !involved_tokens.contains(&swap_data.token_in) {
+                involved_tokens.push(swap_data.token_in.clone());
+            }
+            if !involved_tokens.contains(&swap_data.token_out) {
+                involved_tokens.push(swap_data.token_out.clone());
+            }
+            
+            swap_instructions.push(swap_data);
+        }
+        
         debug!("指令解析完成，找到{}个swap指令", swap_instructions.len());
         
         TransactionInstructionData {
@@ -1432,283 +1612,251 @@ impl MevDetector {
         }
     }
     
-    /// 解析单个swap指令
-    fn parse_swap_instruction(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String], 
-        program_id: &str
-    ) -> Option<SwapInstructionData> {
-        match program_id {
-            program_ids::RAYDIUM_AMM => self.parse_raydium_amm_swap(instruction, account_keys),
-            program_ids::RAYDIUM_CLMM => self.parse_raydium_clmm_swap(instruction, account_keys),
-            program_ids::ORCA_WHIRLPOOLS => self.parse_orca_whirlpool_swap(instruction, account_keys),
-            program_ids::ORCA_V1 => self.parse_orca_v1_swap(instruction, account_keys),
-            program_ids::JUPITER => self.parse_jupiter_swap(instruction, account_keys),
-            program_ids::PUMP_FUN => self.parse_pump_fun_swap(instruction, account_keys),
-            _ => {
-                debug!("未知的DEX程序: {}", program_id);
-                None
-            }
+    /// 没有日志事件时，使用pre/postTokenBalances中的余额变化修正实际成交数量
+    ///
+    /// 优先使用池子金库的变化，其次使用用户代币账户的变化。余额变化是整笔交易的累计值，
+    /// 同一金库在一笔交易中被多次使用时结果只是近似值。
+    fn settle_from_token_balances(
+        &self,
+        tx: &Transaction,
+        account_keys: &[String],
+        swap_data: &mut SwapInstructionData,
+    ) {
+        let change_of = |address: &Option<String>| -> Option<i128> {
+            let address = address.as_ref()?;
+            let index = account_keys.iter().position(|key| key == address)?;
+            tx.token_amount_change(index)
+        };
+        
+        let amount_in = change_of(&swap_data.vault_in)
+            .filter(|change| *change > 0)
+            .or_else(|| change_of(&swap_data.user_source).filter(|change| *change < 0).map(|change| -change));
+        let amount_out = change_of(&swap_data.vault_out)
+            .filter(|change| *change < 0)
+            .map(|change| -change)
+            .or_else(|| change_of(&swap_data.user_destination).filter(|change| *change > 0));
+        
+        if let Some(amount_in) = amount_in.and_then(|amount| u64::try_from(amount).ok()) {
+            swap_data.amount_in = amount_in;
+        }
+        if let Some(amount_out) = amount_out.and_then(|amount| u64::try_from(amount).ok()) {
+            swap_data.amount_out = amount_out;
+        }
+        
+        if amount_in.is_some() || amount_out.is_some() {
+            debug!("余额变化修正swap数量: in={}, out={}", swap_data.amount_in, swap_data.amount_out);
         }
     }
     
-    /// 解析Raydium AMM swap指令
-    fn parse_raydium_amm_swap(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // Raydium AMM swap指令标识符通常是 [9] (swap指令)
-            if data.len() >= 17 && data[0] == 9 {
-                let amount_in = u64::from_le_bytes(data[1..9].try_into().ok()?);
-                let amount_out = u64::from_le_bytes(data[9..17].try_into().ok()?);
-                
-                // 解析账户信息
-                let user_address = account_keys.get(*instruction.accounts.get(16)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1)? as usize)?.clone();
-                
-                // 推断token地址
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Raydium AMM swap: {} -> {}, amount_in: {}, amount_out: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::Raydium,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out,
-                    user_address,
-                    pool_address,
-                });
-            }
-        }
-        None
+    /// 取出与swap指令对应的第一个未使用日志事件（同一程序，且池子地址一致）
+    fn take_matching_event(
+        &self,
+        events: &mut [Option<SwapEvent>],
+        swap_data: &SwapInstructionData,
+    ) -> Option<SwapEvent> {
+        events
+            .iter_mut()
+            .find(|slot| matches!(slot, Some(event) if self.event_matches_swap(event, swap_data)))?
+            .take()
     }
     
-    /// 解析Raydium CLMM swap指令
-    fn parse_raydium_clmm_swap(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // CLMM swap指令可能有不同的标识符
-            if data.len() >= 17 {
-                // 尝试解析金额（位置可能不同）
-                let amount_in = if data.len() >= 9 {
-                    u64::from_le_bytes(data[1..9].try_into().ok()?)
-                } else { 0 };
-                
-                let amount_out = if data.len() >= 17 {
-                    u64::from_le_bytes(data[9..17].try_into().ok()?)
-                } else { 0 };
-                
-                let user_address = account_keys.get(*instruction.accounts.get(0)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1)? as usize)?.clone();
-                
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Raydium CLMM swap: {} -> {}, amount_in: {}, amount_out: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::Raydium,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out,
-                    user_address,
-                    pool_address,
-                });
-            }
+    /// 判断日志事件是否属于该swap指令
+    ///
+    /// 事件必须由解析该swap的同一解码器从同一程序的日志中解码，再由解码器自行判断是否对应。
+    fn event_matches_swap(&self, event: &SwapEvent, swap_data: &SwapInstructionData) -> bool {
+        if event.program_id != swap_data.program_id {
+            return false;
         }
-        None
+        self.decoders
+            .get(&swap_data.program_id)
+            .is_some_and(|decoder| event.decoder == decoder.name() && decoder.event_matches(event, swap_data))
     }
     
-    /// 解析Orca Whirlpool swap指令
-    fn parse_orca_whirlpool_swap(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // Orca whirlpool swap 指令标识
-            if data.len() >= 25 && data[0..8] == [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8] {
-                let amount_in = u64::from_le_bytes(data[8..16].try_into().ok()?);
-                let amount_out = u64::from_le_bytes(data[16..24].try_into().ok()?);
-                
-                let user_address = account_keys.get(*instruction.accounts.get(0)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1)? as usize)?.clone();
-                
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Orca Whirlpool swap: {} -> {}, amount_in: {}, amount_out: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::Orca,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out,
-                    user_address,
-                    pool_address,
-                });
-            }
+    /// 使用日志事件中的精确数据覆盖指令解析的结果
+    fn apply_swap_event(&self, swap_data: &mut SwapInstructionData, event: SwapEvent) {
+        let (amount_in, amount_out) = (event.amount_in, event.amount_out);
+        swap_data.amount_in = amount_in;
+        swap_data.amount_out = amount_out;
+        
+        if let Some(decoder) = self.decoders.get(&swap_data.program_id) {
+            decoder.apply_event(swap_data, &event);
         }
-        None
+        
+        debug!("{}日志事件修正swap数量: in={}, out={}", event.decoder, amount_in, amount_out);
+        swap_data.event = Some(event);
     }
     
-    /// 解析Orca V1 swap指令
-    fn parse_orca_v1_swap(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // Orca V1 swap指令
-            if data.len() >= 17 && data[0] == 1 {
-                let amount_in = u64::from_le_bytes(data[1..9].try_into().ok()?);
-                let amount_out = u64::from_le_bytes(data[9..17].try_into().ok()?);
-                
-                let user_address = account_keys.get(*instruction.accounts.get(0)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1)? as usize)?.clone();
-                
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Orca V1 swap: {} -> {}, amount_in: {}, amount_out: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::Orca,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out,
-                    user_address,
-                    pool_address,
-                });
-            }
-        }
-        None
+    /// 该swap是否由聚合器程序解析得到
+    fn is_aggregator_swap(&self, swap_data: &SwapInstructionData) -> bool {
+        self.decoders
+            .get(&swap_data.program_id)
+            .is_some_and(|decoder| decoder.is_aggregator())
     }
     
-    /// 解析Jupiter swap指令
-    fn parse_jupiter_swap(
-        &self, 
-        instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // Jupiter是聚合器，指令格式可能更复杂
-            if data.len() >= 17 {
-                // 尝试解析基本的swap信息
-                let amount_in = if data.len() >= 9 {
-                    u64::from_le_bytes(data[1..9].try_into().ok()?)
-                } else { 0 };
-                
-                let user_address = account_keys.get(*instruction.accounts.get(0)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1).unwrap_or(&0) as usize)?.clone();
-                
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Jupiter swap: {} -> {}, amount_in: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::Jupiter,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out: 0, // Jupiter可能不直接提供预期输出
-                    user_address,
-                    pool_address,
-                });
-            }
-        }
-        None
+    /// 交易中所有swap涉及的池子/金库等共享账户
+    fn pool_accounts(&self, tx: &Transaction) -> HashSet<String> {
+        self.parse_transaction_instructions(tx)
+            .swap_instructions
+            .iter()
+            .filter_map(|swap| Some(self.decoders.get(&swap.program_id)?.pool_accounts(swap)))
+            .flatten()
+            .collect()
     }
     
-    /// 解析Pump.fun swap指令
-    fn parse_pump_fun_swap(
+    /// 使用注册表中对应的解码器解析单个指令（多跳指令会解析出多个swap）
+    fn parse_swap_instruction(
         &self, 
+        tx: &Transaction,
         instruction: &crate::client::Instruction, 
-        account_keys: &[String]
-    ) -> Option<SwapInstructionData> {
-        if let Ok(data) = bs58::decode(&instruction.data).into_vec() {
-            // Pump.fun的指令格式
-            if data.len() >= 17 {
-                let amount_in = u64::from_le_bytes(data[1..9].try_into().ok()?);
-                let amount_out = u64::from_le_bytes(data[9..17].try_into().ok()?);
-                
-                let user_address = account_keys.get(*instruction.accounts.get(0)? as usize)?.clone();
-                let pool_address = account_keys.get(*instruction.accounts.get(1)? as usize)?.clone();
-                
-                let token_in = self.infer_token_from_accounts(&instruction.accounts, account_keys, true)?;
-                let token_out = self.infer_token_from_accounts(&instruction.accounts, account_keys, false)?;
-                
-                debug!("Pump.fun swap: {} -> {}, amount_in: {}, amount_out: {}",
-                       get_token_symbol(&token_in), get_token_symbol(&token_out), amount_in, amount_out);
-                
-                return Some(SwapInstructionData {
-                    dex_type: DexType::PumpFun,
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out,
-                    user_address,
-                    pool_address,
-                });
-            }
+        account_keys: &[String], 
+        program_id: &str
+    ) -> Vec<SwapInstructionData> {
+        let Some(decoder) = self.decoders.get(program_id) else {
+            debug!("未知的DEX程序: {}", program_id);
+            return Vec::new();
+        };
+        
+        let ctx = InstructionContext {
+            tx,
+            instruction,
+            account_keys,
+            program_id,
+        };
+        decoder.decode_instruction(&ctx)
+    }
+    
+    /// 基于Pump.fun联合曲线状态计算三明治损失
+    ///
+    /// 以前置交易执行前的曲线状态作为用户本应面对的价格，与用户实际成交结果对比。
+    /// 仅当三笔交易都在同一条联合曲线上交易时返回结果。
+    pub async fn calculate_bonding_curve_loss(
+        &self,
+        client: &crate::client::SolanaClient,
+        front_tx_sig: &str,
+        target_tx_sig: &str,
+        back_tx_sig: &str,
+    ) -> Result<Option<UserLoss>, ClientError> {
+        let target_tx = client.get_transaction(target_tx_sig).await?;
+        // 目标交易不是Pump.fun交易时无需获取另外两笔交易
+        if self.find_pump_trade(&target_tx, None).is_none() {
+            return Ok(None);
         }
-        None
+        
+        let transactions = client
+            .get_transactions(&[front_tx_sig, back_tx_sig])
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let [front_tx, back_tx] = transactions.as_slice() else {
+            return Ok(None);
+        };
+        Ok(self.bonding_curve_loss(front_tx, &target_tx, back_tx))
     }
     
-    /// 从账户列表推断token地址
-    fn infer_token_from_accounts(
-        &self, 
-        accounts: &[u8], 
-        account_keys: &[String], 
-        is_input: bool
-    ) -> Option<String> {
-        // 根据账户位置推断token
-        // 通常input token在前面的位置，output token在后面
-        let start_idx = if is_input { 2 } else { 4 };
-        let end_idx = if is_input { 6 } else { 8 };
-        
-        for i in start_idx..end_idx.min(accounts.len()) {
-            if let Some(account) = account_keys.get(accounts[i] as usize) {
-                // 检查是否是已知的token地址
-                if self.is_known_token(account) {
-                    return Some(account.clone());
-                }
-            }
+    /// 根据三笔交易中的Pump.fun TradeEvent计算联合曲线损失
+    fn bonding_curve_loss(
+        &self,
+        front_tx: &Transaction,
+        target_tx: &Transaction,
+        back_tx: &Transaction,
+    ) -> Option<UserLoss> {
+        let (target_swap, target_event) = self.find_pump_trade(target_tx, None)?;
+        let (_, front_event) = self.find_pump_trade(front_tx, Some(&target_event.mint))?;
+        // 前置交易必须与用户同向，才会推高用户的成交价格
+        if front_event.is_buy != target_event.is_buy {
+            debug!("前置交易与用户交易方向相反，不构成联合曲线三明治");
+            return None;
         }
+        let back_event = self
+            .find_pump_trade(back_tx, Some(&target_event.mint))
+            .map(|(_, e)| e)
+            .filter(|back| back.is_buy != front_event.is_buy);
+        
+        // 用户在没有前置交易时本应面对的曲线状态
+        let fair_curve = front_event.curve_before();
+        debug!("联合曲线: 攻击前 {:?}, 用户成交前 {:?}", fair_curve, target_event.curve_before());
+        
+        let loss_lamports = if target_event.is_buy {
+            let expected_tokens = fair_curve.quote_buy(target_event.sol_amount);
+            let missing_tokens = expected_tokens.saturating_sub(target_event.token_amount);
+            if target_event.token_amount == 0 {
+                return None;
+            }
+            // 按用户实际成交均价将少得的代币折算为SOL
+            (missing_tokens as u128 * target_event.sol_amount as u128
+                / target_event.token_amount as u128) as u64
+        } else {
+            let expected_sol = fair_curve.quote_sell(target_event.token_amount);
+            expected_sol.saturating_sub(target_event.sol_amount)
+        };
+        
+        let attacker_profit = match &back_event {
+            Some(back) if front_event.is_buy && !back.is_buy => {
+                back.sol_amount.saturating_sub(front_event.sol_amount)
+            }
+            _ => 0,
+        };
         
-        // 如果没找到已知token，返回第一个可能的token账户
-        if let Some(account_idx) = accounts.get(start_idx) {
-            account_keys.get(*account_idx as usize).cloned()
+        let loss_percentage = if target_event.sol_amount > 0 {
+            loss_lamports as f64 / target_event.sol_amount as f64 * 100.0
         } else {
-            None
+            0.0
+        };
+        
+        // 三明治机器人通常会把用户的成交推到滑点边界附近
+        let slippage_headroom = target_swap.slippage_headroom();
+        debug!("联合曲线分析: 用户损失 {} lamports ({:.2}%), 攻击者利润 {} lamports, 剩余滑点空间 {:?}",
+               loss_lamports, loss_percentage, attacker_profit, slippage_headroom);
+        
+        if loss_lamports <= 1000 {
+            return None;
         }
+        
+        let token_losses = vec![TokenLossDetail {
+            token_address: WSOL.to_string(),
+            token_symbol: "SOL".to_string(),
+            loss_amount: loss_lamports,
+            loss_amount_ui: loss_lamports as f64 / 1_000_000_000.0,
+            equivalent_amount: None,
+            equivalent_symbol: None,
+        }];
+        let primary_loss_token = self.identify_primary_loss_token(&token_losses);
+        
+        Some(UserLoss {
+            estimated_loss_lamports: loss_lamports,
+            loss_percentage,
+            calculation_method: "Pump.fun联合曲线分析法".to_string(),
+            mev_profit_lamports: attacker_profit,
+            mev_profit_token: Some("SOL".to_string()),
+            mev_profit_amount: attacker_profit as f64 / 1_000_000_000.0,
+            confidence_score: match (back_event.is_some(), slippage_headroom) {
+                (true, Some(headroom)) if headroom < 0.01 => 0.95,
+                (true, _) => 0.9,
+                (false, _) => 0.8,
+            },
+            validation_passed: loss_percentage <= 20.0,
+            token_losses,
+            primary_loss_token,
+        })
     }
     
-    /// 检查是否是已知的token
-    fn is_known_token(&self, address: &str) -> bool {
-        matches!(address, 
-            WSOL | USDC | USDT | RAY | BONK | WIF |
-            "11111111111111111111111111111111" | // System program
-            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" // Token program
-        )
+    /// 查找交易中带TradeEvent的Pump.fun swap（可按mint过滤）
+    fn find_pump_trade(
+        &self,
+        tx: &Transaction,
+        mint: Option<&str>,
+    ) -> Option<(SwapInstructionData, PumpFunTradeEvent)> {
+        self.parse_transaction_instructions(tx)
+            .swap_instructions
+            .into_iter()
+            .find_map(|swap| {
+                let event = swap.event.as_ref()?.venue_data::<PumpFunTradeEvent>()?;
+                if mint.is_some_and(|m| m != event.mint) {
+                    return None;
+                }
+                let event = event.clone();
+                Some((swap, event))
+            })
     }
     
     /// 基于指令解析数据计算更精确的损失
@@ -1718,18 +1866,23 @@ impl MevDetector {
         front_tx_sig: &str,
         target_tx_sig: &str,
         back_tx_sig: &str,
-    ) -> Option<UserLoss> {
+    ) -> Result<Option<UserLoss>, ClientError> {
         debug!("开始基于指令解析的损失计算");
         
-        // 获取三个交易
-        let front_tx = client.get_transaction(front_tx_sig).await.ok()?;
-        let target_tx = client.get_transaction(target_tx_sig).await.ok()?;
-        let back_tx = client.get_transaction(back_tx_sig).await.ok()?;
+        // 批量获取三个交易
+        let transactions = client
+            .get_transactions(&[front_tx_sig, target_tx_sig, back_tx_sig])
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let [front_tx, target_tx, back_tx] = transactions.as_slice() else {
+            return Ok(None);
+        };
         
         // 解析每个交易的指令数据
-        let front_data = self.parse_transaction_instructions(&front_tx);
-        let target_data = self.parse_transaction_instructions(&target_tx);
-        let back_data = self.parse_transaction_instructions(&back_tx);
+        let front_data = self.parse_transaction_instructions(front_tx);
+        let target_data = self.parse_transaction_instructions(target_tx);
+        let back_data = self.parse_transaction_instructions(back_tx);
         
         debug!("指令解析完成 - 前置:{}个swap, 目标:{}个swap, 后置:{}个swap",
                front_data.swap_instructions.len(),
@@ -1769,7 +1922,7 @@ impl MevDetector {
         let primary_loss_token = self.identify_primary_loss_token(&token_losses);
         
         if validation_passed && estimated_loss > 1000 {
-            Some(UserLoss {
+            Ok(Some(UserLoss {
                 estimated_loss_lamports: estimated_loss,
                 loss_percentage: loss_percentage.min(15.0),
                 calculation_method: "指令解析分析法".to_string(),
@@ -1780,9 +1933,9 @@ impl MevDetector {
                 validation_passed,
                 token_losses,
                 primary_loss_token,
-            })
+            }))
         } else {
-            None
+            Ok(None)
         }
     }
     
@@ -1890,4 +2043,209 @@ impl MevDetector {
         
         confidence.min(0.9) // 最高90%置信度（指令解析可能有误差）
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::decoders::test_support::{pubkey, transaction};
+    use crate::decoders::{InstructionContext, SwapDecoder};
+
+    fn detector(window_transactions: usize) -> MevDetector {
+        let config = MevDetectionConfig {
+            window_transactions,
+            ..MevDetectionConfig::default()
+        };
+        MevDetector::new(config, Language::English)
+    }
+
+    #[test]
+    fn sandwich_search_respects_window() {
+        let block = vec![
+            pool_swap("front", 1, 100),
+            pool_swap("other1", 5, 200),
+            pool_swap("victim", 2, 100),
+            pool_swap("other2", 6, 201),
+            pool_swap("back", 1, 100),
+        ];
+
+        // 窗口为0表示不限制，计算搜索范围时不能溢出
+        let sandwich = detector(0)
+            .detect_sandwich_attack(&block, "victim")
+            .expect("应检测到三明治");
+        assert_eq!((sandwich.front_tx.as_str(), sandwich.back_tx.as_str()), ("front", "back"));
+        assert!(detector(0).detect_sandwich_attack(&block[2..], "victim").is_none());
+
+        // 前置/后置交易距受害交易2笔，窗口为1时不在范围内
+        assert!(detector(1).detect_sandwich_attack(&block, "victim").is_none());
+        assert!(detector(2).detect_sandwich_attack(&block, "victim").is_some());
+    }
+
+    /// 外部注册的解码器：程序ID和事件类型都不在内置列表中
+    struct ForkDecoder;
+
+    /// 分叉场所事件中的专有字段
+    #[derive(Debug)]
+    struct ForkSwapFee(u64);
+
+    impl SwapDecoder for ForkDecoder {
+        fn name(&self) -> &'static str {
+            "Fork AMM"
+        }
+
+        fn program_ids(&self) -> &[&str] {
+            &["Fork111111111111111111111111111111111111111"]
+        }
+
+        fn decode_instruction(&self, ctx: &InstructionContext) -> Vec<SwapInstructionData> {
+            vec![SwapInstructionData {
+                venue: self.name(),
+                program_id: ctx.program_id.to_string(),
+                token_in: WSOL.to_string(),
+                token_out: USDC.to_string(),
+                amount_in: 1_000,
+                amount_out: 0,
+                user_address: ctx.account(0).unwrap(),
+                pool_address: ctx.account(1).unwrap(),
+                amount_specified_is_input: true,
+                other_amount_threshold: 0,
+                user_source: None,
+                user_destination: None,
+                vault_in: None,
+                vault_out: None,
+                sqrt_price_limit: None,
+                event: None,
+            }]
+        }
+
+        /// `fork_swap: <池子> <输入> <输出> <手续费>`
+        fn decode_log_message(&self, message: &str) -> Option<SwapEvent> {
+            let mut fields = message.strip_prefix("fork_swap: ")?.split(' ');
+            let pool = fields.next()?.to_string();
+            let amount_in = fields.next()?.parse().ok()?;
+            let amount_out = fields.next()?.parse().ok()?;
+            let fee = fields.next()?.parse().ok()?;
+            Some(SwapEvent::new(amount_in, amount_out).with_pool(pool).with_venue_data(ForkSwapFee(fee)))
+        }
+    }
+
+    #[test]
+    fn registered_decoder_attaches_its_own_events() {
+        let program_id = ForkDecoder.program_ids()[0];
+        let mut tx = transaction(program_id, &[pubkey(1), pubkey(2)], &[], &[]);
+        tx.meta.as_mut().unwrap().log_messages = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("Program log: fork_swap: {} 1000 950 3", pubkey(2)),
+            format!("Program {} success", program_id),
+        ];
+
+        let mut decoders = DecoderRegistry::default();
+        decoders.register(Box::new(ForkDecoder));
+        let detector = MevDetector::with_decoders(MevDetectionConfig::default(), Language::English, decoders);
+
+        let swaps = detector.parse_transaction_instructions(&tx).swap_instructions;
+        assert_eq!(swaps.len(), 1);
+        let swap = &swaps[0];
+        assert_eq!(swap.venue, "Fork AMM");
+        assert_eq!((swap.amount_in, swap.amount_out), (1_000, 950));
+        let event = swap.event.as_ref().expect("事件应对应到swap");
+        assert_eq!(event.program_id, program_id);
+        assert_eq!(event.decoder, "Fork AMM");
+        assert_eq!(event.venue_data::<ForkSwapFee>().map(|fee| fee.0), Some(3));
+        assert!(event.venue_data::<PumpFunTradeEvent>().is_none());
+    }
+
+    #[test]
+    fn bonding_curve_loss_requires_same_direction_front_run() {
+        use crate::decoders::test_support::pump_trade_with_event;
+
+        let detector = detector(10);
+        let victim = pump_trade_with_event(true, 1_000_000_000, 30_000_000_000_000);
+        let back = pump_trade_with_event(false, 520_000_000, 16_000_000_000_000);
+
+        let front = pump_trade_with_event(true, 500_000_000, 16_000_000_000_000);
+        let loss = detector
+            .bonding_curve_loss(&front, &victim, &back)
+            .expect("同向前置交易应计算出损失");
+        assert!(loss.estimated_loss_lamports > 0);
+        assert_eq!(loss.mev_profit_lamports, 20_000_000);
+
+        // 前置交易卖出不会抬高用户的买入价格
+        let front = pump_trade_with_event(false, 500_000_000, 16_000_000_000_000);
+        assert!(detector.bonding_curve_loss(&front, &victim, &back).is_none());
+    }
+
+    /// 由`signer`发起、写入`pool`的DEX交易
+    fn pool_swap(signature: &str, signer: u8, pool: u8) -> Transaction {
+        let mut tx = transaction(RAYDIUM_AMM, &[pubkey(signer), pubkey(pool)], &[9], &[]);
+        tx.signature = signature.to_string();
+        tx
+    }
+
+    #[test]
+    fn per_signature_detection_uses_block_scan_rules() {
+        let detector = detector(10);
+        let block = vec![
+            pool_swap("front", 1, 100),
+            pool_swap("victim", 2, 100),
+            pool_swap("back", 1, 100),
+        ];
+        let sandwich = detector
+            .detect_sandwich_attack(&block, "victim")
+            .expect("应检测到三明治");
+        assert_eq!((sandwich.front_tx.as_str(), sandwich.back_tx.as_str()), ("front", "back"));
+        assert_eq!(sandwich.account_intersection, vec![pubkey(100)]);
+
+        // 前置和后置交易不是同一签名者
+        let block = vec![
+            pool_swap("front", 1, 100),
+            pool_swap("victim", 2, 100),
+            pool_swap("back", 3, 100),
+        ];
+        assert!(detector.detect_sandwich_attack(&block, "victim").is_none());
+
+        // 受害者自己的前后交易不算攻击
+        let block = vec![
+            pool_swap("front", 2, 100),
+            pool_swap("victim", 2, 100),
+            pool_swap("back", 2, 100),
+        ];
+        assert!(detector.detect_sandwich_attack(&block, "victim").is_none());
+    }
+
+    /// 按(代币地址, 损失数量)构造的损失，`estimated_loss_lamports`取第一项
+    pub(crate) fn user_loss(token_losses: &[(&str, u64)]) -> UserLoss {
+        UserLoss {
+            estimated_loss_lamports: token_losses.first().map_or(0, |&(_, amount)| amount),
+            loss_percentage: 1.0,
+            calculation_method: "test".to_string(),
+            mev_profit_lamports: 0,
+            mev_profit_token: None,
+            mev_profit_amount: 0.0,
+            confidence_score: 1.0,
+            validation_passed: true,
+            token_losses: token_losses
+                .iter()
+                .map(|&(token_address, loss_amount)| TokenLossDetail {
+                    token_address: token_address.to_string(),
+                    token_symbol: get_token_symbol(token_address).to_string(),
+                    loss_amount,
+                    loss_amount_ui: loss_amount as f64 / 10f64.powi(i32::from(get_token_decimals(token_address))),
+                    equivalent_amount: None,
+                    equivalent_symbol: None,
+                })
+                .collect(),
+            primary_loss_token: token_losses.first().map(|&(token_address, _)| token_address.to_string()),
+        }
+    }
+
+    #[test]
+    fn sol_loss_counts_only_sol_token_losses() {
+        assert_eq!(user_loss(&[(WSOL, 5_000), (USDC, 2_000_000)]).sol_loss_lamports(), 5_000);
+        // 主要损失是USDC时estimated_loss_lamports以USDC为单位，不计入SOL损失
+        assert_eq!(user_loss(&[(USDC, 2_000_000)]).sol_loss_lamports(), 0);
+
+        let mut without_details = user_loss(&[]);
+        without_details.estimated_loss_lamports = 7_000;
+        assert_eq!(without_details.sol_loss_lamports(), 7_000);
+    }
+}
@@ -0,0 +1,156 @@
+//! RPC请求的客户端限流与重试退避
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 令牌桶：按固定速率补充令牌，容量即允许的突发请求数
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_second: f64) -> Self {
+        let capacity = requests_per_second.max(1.0);
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: requests_per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 取走一个令牌；令牌不足时返回需要等待的时间
+    fn try_acquire(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_sec))
+        }
+    }
+}
+
+/// 按RPC方法分别限流
+///
+/// 每个方法一个令牌桶，速率为`method_limits`中的配置，未配置的方法使用`default_rate`。
+/// 速率不大于0的方法不限流。方法名不区分大小写（配置文件的键会被转为小写）。
+#[derive(Debug)]
+pub struct MethodRateLimiter {
+    default_rate: f64,
+    method_limits: HashMap<String, f64>,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl MethodRateLimiter {
+    pub fn new(default_rate: f64, method_limits: HashMap<String, f64>) -> Self {
+        Self {
+            default_rate,
+            method_limits: method_limits
+                .into_iter()
+                .map(|(method, rate)| (method.to_lowercase(), rate))
+                .collect(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn rate_for(&self, method: &str) -> f64 {
+        self.method_limits
+            .get(&method.to_lowercase())
+            .copied()
+            .unwrap_or(self.default_rate)
+    }
+
+    /// 等待直到该方法有可用的请求额度
+    pub async fn acquire(&self, method: &str) {
+        let rate = self.rate_for(method);
+        if rate <= 0.0 {
+            return;
+        }
+
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+                buckets
+                    .entry(method.to_string())
+                    .or_insert_with(|| TokenBucket::new(rate))
+                    .try_acquire()
+            };
+            match wait {
+                None => return,
+                Some(delay) => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+}
+
+/// 指数退避参数
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Backoff {
+    /// 第`attempt`次重试（从1开始）前的等待时间
+    ///
+    /// 基础时间按2的幂增长并以`max`为上限，在[一半, 全部]区间内随机抖动，
+    /// 避免多个请求同时被限流后又同时重试。
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial
+            .saturating_mul(1u32 << attempt.saturating_sub(1).min(16))
+            .min(self.max);
+        let half = exponential / 2;
+        half + half.mul_f64(jitter())
+    }
+
+    /// 服务器建议的等待时间（`Retry-After`），以`max`为上限
+    pub fn clamp(&self, suggested: Duration) -> Duration {
+        suggested.min(self.max)
+    }
+}
+
+/// [0, 1)区间的随机数，标准库的`RandomState`每次构造都使用新的随机种子
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backoff() -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(500),
+            max: Duration::from_millis(10_000),
+        }
+    }
+
+    #[test]
+    fn clamps_server_retry_after_to_max_backoff() {
+        assert_eq!(backoff().clamp(Duration::from_secs(3)), Duration::from_secs(3));
+        assert_eq!(backoff().clamp(Duration::from_secs(86_400)), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn delay_stays_within_max_backoff() {
+        for attempt in [1, 5, 40, u32::MAX] {
+            assert!(backoff().delay(attempt) <= Duration::from_millis(10_000));
+        }
+        assert!(backoff().delay(1) >= Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,120 @@
+//! 多RPC端点管理：优先级、方法限制、健康状态
+
+use crate::rate_limit::MethodRateLimiter;
+use crate::settings::{RpcConfig, RpcEndpointConfig};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 单个RPC端点
+#[derive(Debug)]
+pub struct RpcEndpoint {
+    /// 日志和结果中显示的名称
+    pub name: String,
+    pub url: String,
+    pub priority: u32,
+    pub headers: HeaderMap,
+    methods: Vec<String>,
+    disabled_methods: Vec<String>,
+    /// 每个端点独立限流（不同服务商的API key额度不同）
+    pub rate_limiter: MethodRateLimiter,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl RpcEndpoint {
+    fn from_config(config: &RpcEndpointConfig, rpc: &RpcConfig) -> Result<Self, String> {
+        let mut headers = HeaderMap::new();
+        for (key, value) in &config.headers {
+            let name = HeaderName::from_bytes(key.as_bytes())
+                .map_err(|e| format!("无效的请求头名称{}: {}", key, e))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| format!("无效的请求头{}: {}", key, e))?;
+            headers.insert(name, value);
+        }
+
+        let name = config.name.clone().unwrap_or_else(|| {
+            Url::parse(&config.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| "rpc".to_string())
+        });
+
+        Ok(Self {
+            name,
+            url: config.url.clone(),
+            priority: config.priority,
+            headers,
+            methods: config.methods.clone(),
+            disabled_methods: config.disabled_methods.clone(),
+            rate_limiter: MethodRateLimiter::new(
+                rpc.requests_per_second,
+                rpc.method_rate_limits.clone(),
+            ),
+            unhealthy_until: Mutex::new(None),
+        })
+    }
+
+    /// 该端点是否用于指定的RPC方法
+    pub fn supports(&self, method: &str) -> bool {
+        (self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method)))
+            && !self.disabled_methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        match *self.unhealthy_until.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(until) => Instant::now() >= until,
+            None => true,
+        }
+    }
+
+    /// 标记为不健康，冷却期内排在健康端点之后
+    pub fn mark_unhealthy(&self, cooldown: Duration) {
+        *self.unhealthy_until.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + cooldown);
+    }
+
+    pub fn mark_healthy(&self) {
+        *self.unhealthy_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// 按优先级排列的RPC端点集合
+#[derive(Debug)]
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    pub unhealthy_cooldown: Duration,
+}
+
+impl RpcPool {
+    pub fn new(endpoints: &[RpcEndpointConfig], rpc: &RpcConfig) -> Result<Self, String> {
+        let mut endpoints = endpoints
+            .iter()
+            .map(|config| RpcEndpoint::from_config(config, rpc))
+            .collect::<Result<Vec<_>, _>>()?;
+        if endpoints.is_empty() {
+            return Err("未配置RPC端点".to_string());
+        }
+        // 稳定排序，同优先级保持配置顺序
+        endpoints.sort_by_key(|endpoint| endpoint.priority);
+
+        Ok(Self {
+            endpoints,
+            unhealthy_cooldown: Duration::from_secs(rpc.unhealthy_cooldown_secs),
+        })
+    }
+
+    pub fn endpoints(&self) -> &[RpcEndpoint] {
+        &self.endpoints
+    }
+
+    /// 可处理该方法的端点：健康端点按优先级在前，不健康的端点作为最后手段
+    pub fn candidates(&self, method: &str) -> Vec<&RpcEndpoint> {
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.supports(method))
+            .partition(|endpoint| endpoint.is_healthy());
+        healthy.extend(unhealthy);
+        healthy
+    }
+}
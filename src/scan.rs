@@ -0,0 +1,445 @@
+//! 按slot范围批量扫描三明治攻击
+//!
+//! 多个区块并发扫描（并发数有上限），被leader跳过的slot直接略过。
+//! 扫描进度定期写入本地检查点文件，中断后再次运行同一范围会从上次完成的位置继续，
+//! 结束时根据检查点中累计的结果生成汇总。
+
+use crate::client::{ClientError, SolanaClient};
+use crate::mev::{BlockSandwich, MevDetector, UserLoss};
+use futures_util::stream::{self, StreamExt};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 每完成这么多个slot写一次检查点
+const CHECKPOINT_INTERVAL: usize = 20;
+
+/// 汇总中列出的攻击者数量
+const TOP_ATTACKERS: usize = 10;
+
+/// 检查点中保存的一次三明治攻击
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandwichRecord {
+    pub slot: u64,
+    pub attacker: String,
+    pub front_tx: String,
+    pub back_tx: String,
+    pub victims: Vec<String>,
+    /// 所有受害交易以SOL计的估算损失之和（无法计算损失的受害交易不计入，其他代币的损失不计入）
+    pub victim_loss_lamports: u64,
+    pub pools: Vec<String>,
+    /// 区块中与受害交易共享账户、执行失败的DEX交易数（多笔受害交易共享的只计一次）
+    #[serde(default)]
+    pub failed_attempts: usize,
+}
+
+impl SandwichRecord {
+    fn from_sandwich(slot: u64, sandwich: &BlockSandwich) -> Self {
+        Self {
+            slot,
+            attacker: sandwich.attacker.clone(),
+            front_tx: sandwich.front.signature.clone(),
+            back_tx: sandwich.back.signature.clone(),
+            victims: sandwich
+                .victims
+                .iter()
+                .map(|victim| victim.transaction.signature.clone())
+                .collect(),
+            victim_loss_lamports: sandwich
+                .victims
+                .iter()
+                .filter_map(|victim| victim.loss.as_ref())
+                .map(UserLoss::sol_loss_lamports)
+                .sum(),
+            pools: sandwich.pools.clone(),
+            failed_attempts: sandwich
+                .victims
+                .iter()
+                .flat_map(|victim| &victim.failed_attempts)
+                .map(|attempt| attempt.signature.as_str())
+                .collect::<BTreeSet<_>>()
+                .len(),
+        }
+    }
+}
+
+/// 扫描检查点
+///
+/// 只记录进度和发现的攻击，没有攻击的slot不单独保存，检查点大小与扫描范围无关。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub start_slot: u64,
+    pub end_slot: u64,
+    /// 该slot之前（不含）的所有slot都已完成
+    pub next_slot: u64,
+    /// `next_slot`之后已完成的slot（并发扫描时乱序完成）
+    #[serde(default)]
+    pub completed_ahead: BTreeSet<u64>,
+    /// 被leader跳过（没有区块）的slot数
+    #[serde(default)]
+    pub skipped_slots: u64,
+    /// 获取失败的slot，续扫时会重试
+    #[serde(default)]
+    pub failed_slots: BTreeSet<u64>,
+    #[serde(default)]
+    pub sandwiches: Vec<SandwichRecord>,
+}
+
+impl ScanCheckpoint {
+    pub fn new(start_slot: u64, end_slot: u64) -> Self {
+        Self {
+            start_slot,
+            end_slot,
+            next_slot: start_slot,
+            completed_ahead: BTreeSet::new(),
+            skipped_slots: 0,
+            failed_slots: BTreeSet::new(),
+            sandwiches: Vec::new(),
+        }
+    }
+
+    /// 读取检查点；文件不存在时返回`None`
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 写入检查点（先写临时文件再重命名，避免中断时留下不完整的文件）
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// 还需要扫描的slot（升序）
+    pub fn pending_slots(&self) -> Vec<u64> {
+        (self.next_slot..=self.end_slot)
+            .filter(|slot| !self.completed_ahead.contains(slot))
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_slot > self.end_slot
+    }
+
+    /// 记录一个slot已完成，并推进连续完成的位置
+    fn complete(&mut self, slot: u64) {
+        self.failed_slots.remove(&slot);
+        self.completed_ahead.insert(slot);
+        while self.completed_ahead.remove(&self.next_slot) {
+            self.next_slot += 1;
+        }
+    }
+
+    /// 根据累计的结果生成汇总
+    pub fn summary(&self) -> ScanSummary {
+        let mut per_slot: BTreeMap<u64, usize> = BTreeMap::new();
+        let mut attackers: HashMap<&str, AttackerSummary> = HashMap::new();
+        for record in &self.sandwiches {
+            *per_slot.entry(record.slot).or_default() += 1;
+            let attacker = attackers
+                .entry(&record.attacker)
+                .or_insert_with(|| AttackerSummary {
+                    attacker: record.attacker.clone(),
+                    sandwiches: 0,
+                    victims: 0,
+                    victim_loss_lamports: 0,
+                });
+            attacker.sandwiches += 1;
+            attacker.victims += record.victims.len();
+            attacker.victim_loss_lamports += record.victim_loss_lamports;
+        }
+
+        let mut top_attackers: Vec<AttackerSummary> = attackers.into_values().collect();
+        top_attackers.sort_by(|a, b| {
+            b.sandwiches
+                .cmp(&a.sandwiches)
+                .then(b.victim_loss_lamports.cmp(&a.victim_loss_lamports))
+                .then_with(|| a.attacker.cmp(&b.attacker))
+        });
+        top_attackers.truncate(TOP_ATTACKERS);
+
+        let completed = self.next_slot.saturating_sub(self.start_slot) + self.completed_ahead.len() as u64;
+        ScanSummary {
+            start_slot: self.start_slot,
+            end_slot: self.end_slot,
+            scanned_slots: completed.saturating_sub(self.skipped_slots),
+            skipped_slots: self.skipped_slots,
+            failed_slots: self.failed_slots.iter().copied().collect(),
+            total_sandwiches: self.sandwiches.len(),
+            total_victims: self.sandwiches.iter().map(|record| record.victims.len()).sum(),
+            total_victim_loss_lamports: self.sandwiches.iter().map(|record| record.victim_loss_lamports).sum(),
+            total_failed_attempts: self.sandwiches.iter().map(|record| record.failed_attempts).sum(),
+            sandwiches_per_slot: per_slot,
+            top_attackers,
+        }
+    }
+}
+
+/// 单个攻击者的汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct AttackerSummary {
+    pub attacker: String,
+    pub sandwiches: usize,
+    pub victims: usize,
+    /// 受害者以SOL计的总损失
+    pub victim_loss_lamports: u64,
+}
+
+/// 范围扫描的汇总结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanSummary {
+    pub start_slot: u64,
+    pub end_slot: u64,
+    /// 已扫描的有区块的slot数
+    pub scanned_slots: u64,
+    pub skipped_slots: u64,
+    pub failed_slots: Vec<u64>,
+    pub total_sandwiches: usize,
+    pub total_victims: usize,
+    /// 以SOL计的受害者总损失
+    pub total_victim_loss_lamports: u64,
+    /// 受害交易附近执行失败的攻击尝试数
+    pub total_failed_attempts: usize,
+    /// 发现三明治攻击的slot及其攻击次数
+    pub sandwiches_per_slot: BTreeMap<u64, usize>,
+    /// 按攻击次数排序的攻击者
+    pub top_attackers: Vec<AttackerSummary>,
+}
+
+/// 单个slot的扫描结果
+enum SlotOutcome {
+    Scanned(Vec<BlockSandwich>),
+    Skipped,
+    Failed(ClientError),
+}
+
+impl SlotOutcome {
+    /// 只有节点明确返回slot被跳过（-32007/-32009）时才视为跳过；
+    /// 其他错误（包括区块为空的NotFound）都记为失败，续扫时重试
+    fn from_result(result: Result<Vec<BlockSandwich>, ClientError>) -> Self {
+        match result {
+            Ok(sandwiches) => SlotOutcome::Scanned(sandwiches),
+            Err(e) if e.is_slot_skipped() => SlotOutcome::Skipped,
+            Err(e) => SlotOutcome::Failed(e),
+        }
+    }
+}
+
+/// 并发扫描检查点中尚未完成的slot，定期保存检查点
+///
+/// 收到Ctrl-C时保存检查点后提前返回，之后可从中断处继续。
+///
+/// # 参数
+/// - `concurrency`: 同时扫描的区块数上限（RPC限流仍按配置生效）。
+///
+/// # 返回
+/// 写入检查点文件失败时返回错误。
+pub async fn scan_slot_range(
+    client: &SolanaClient,
+    detector: &MevDetector,
+    checkpoint: &mut ScanCheckpoint,
+    checkpoint_path: &Path,
+    concurrency: usize,
+) -> io::Result<()> {
+    let pending = checkpoint.pending_slots();
+    let total = pending.len();
+    info!("待扫描 {} 个slot（{} - {}），并发数 {}",
+          total, checkpoint.next_slot, checkpoint.end_slot, concurrency);
+
+    let mut results = stream::iter(pending)
+        .map(|slot| async move {
+            (slot, SlotOutcome::from_result(detector.scan_block(client, slot).await))
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    let mut done = 0;
+    let mut interrupted = false;
+    loop {
+        let (slot, outcome) = tokio::select! {
+            next = results.next() => match next {
+                Some(result) => result,
+                None => break,
+            },
+            _ = &mut ctrl_c => {
+                interrupted = true;
+                break;
+            }
+        };
+
+        match outcome {
+            SlotOutcome::Scanned(sandwiches) => {
+                checkpoint
+                    .sandwiches
+                    .extend(sandwiches.iter().map(|sandwich| SandwichRecord::from_sandwich(slot, sandwich)));
+                checkpoint.complete(slot);
+            }
+            SlotOutcome::Skipped => {
+                debug!("slot {}被跳过", slot);
+                checkpoint.skipped_slots += 1;
+                checkpoint.complete(slot);
+            }
+            SlotOutcome::Failed(e) => {
+                warn!("扫描slot {}失败，续扫时将重试: {}", slot, e);
+                checkpoint.failed_slots.insert(slot);
+            }
+        }
+
+        done += 1;
+        if done % CHECKPOINT_INTERVAL == 0 {
+            checkpoint.save(checkpoint_path)?;
+            info!("已扫描 {} / {} 个slot，发现 {} 次三明治攻击",
+                  done, total, checkpoint.sandwiches.len());
+        }
+    }
+
+    checkpoint.save(checkpoint_path)?;
+    if interrupted {
+        warn!("扫描已中断，进度已保存到{}，再次运行将从slot {}继续",
+              checkpoint_path.display(), checkpoint.next_slot);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(slot: u64, attacker: &str, victims: usize, loss: u64) -> SandwichRecord {
+        SandwichRecord {
+            slot,
+            attacker: attacker.to_string(),
+            front_tx: format!("front{}", slot),
+            back_tx: format!("back{}", slot),
+            victims: (0..victims).map(|i| format!("victim{}_{}", slot, i)).collect(),
+            victim_loss_lamports: loss,
+            pools: Vec::new(),
+            failed_attempts: victims,
+        }
+    }
+
+    #[test]
+    fn complete_advances_over_out_of_order_slots() {
+        let mut checkpoint = ScanCheckpoint::new(100, 105);
+        checkpoint.failed_slots.insert(101);
+
+        checkpoint.complete(102);
+        checkpoint.complete(104);
+        assert_eq!(checkpoint.next_slot, 100);
+        assert_eq!(checkpoint.pending_slots(), vec![100, 101, 103, 105]);
+
+        checkpoint.complete(100);
+        assert_eq!(checkpoint.next_slot, 101);
+
+        // 重试成功的slot从失败列表中移除
+        checkpoint.complete(101);
+        assert_eq!(checkpoint.next_slot, 103);
+        assert!(checkpoint.failed_slots.is_empty());
+        assert_eq!(checkpoint.completed_ahead, BTreeSet::from([104]));
+        assert_eq!(checkpoint.pending_slots(), vec![103, 105]);
+
+        checkpoint.complete(105);
+        checkpoint.complete(103);
+        assert!(checkpoint.is_complete());
+        assert!(checkpoint.completed_ahead.is_empty());
+        assert!(checkpoint.pending_slots().is_empty());
+    }
+
+    #[test]
+    fn summary_counts_out_of_order_progress() {
+        let mut checkpoint = ScanCheckpoint::new(10, 19);
+        for slot in [10, 11, 15, 17] {
+            checkpoint.complete(slot);
+        }
+        checkpoint.skipped_slots = 1;
+        checkpoint.failed_slots.insert(12);
+        checkpoint.sandwiches = vec![
+            record(11, "bot_a", 2, 300),
+            record(15, "bot_b", 1, 900),
+            record(15, "bot_a", 1, 100),
+            record(17, "bot_c", 1, 50),
+        ];
+
+        let summary = checkpoint.summary();
+        // 已完成4个slot，其中1个被跳过
+        assert_eq!(summary.scanned_slots, 3);
+        assert_eq!(summary.skipped_slots, 1);
+        assert_eq!(summary.failed_slots, vec![12]);
+        assert_eq!(summary.total_sandwiches, 4);
+        assert_eq!(summary.total_victims, 5);
+        assert_eq!(summary.total_victim_loss_lamports, 1_350);
+        assert_eq!(summary.total_failed_attempts, 5);
+        assert_eq!(summary.sandwiches_per_slot, BTreeMap::from([(11, 1), (15, 2), (17, 1)]));
+
+        let attackers: Vec<(&str, usize, usize, u64)> = summary
+            .top_attackers
+            .iter()
+            .map(|a| (a.attacker.as_str(), a.sandwiches, a.victims, a.victim_loss_lamports))
+            .collect();
+        assert_eq!(attackers, vec![("bot_a", 2, 3, 400), ("bot_b", 1, 1, 900), ("bot_c", 1, 1, 50)]);
+    }
+
+    #[test]
+    fn record_counts_only_sol_victim_losses() {
+        use crate::mev::tests::user_loss;
+        use crate::mev::token_info::{USDC, WSOL};
+        use crate::mev::{FailedAttackAttempt, SandwichVictim, ScannedTransaction};
+
+        let scanned = |signature: &str| ScannedTransaction {
+            signature: signature.to_string(),
+            slot: 7,
+            block_index: None,
+            signer: String::new(),
+        };
+        let attempt = |signature: &str| FailedAttackAttempt {
+            signature: signature.to_string(),
+            slot: 7,
+            block_index: None,
+            is_before_target: true,
+            error: String::new(),
+            account_intersection: Vec::new(),
+        };
+        let victim = |signature: &str, loss, failed: &[&str]| SandwichVictim {
+            transaction: scanned(signature),
+            loss,
+            failed_attempts: failed.iter().map(|&signature| attempt(signature)).collect(),
+        };
+        let sandwich = BlockSandwich {
+            attacker: "bot".to_string(),
+            front: scanned("front"),
+            back: scanned("back"),
+            victims: vec![
+                victim("sol", Some(user_loss(&[(WSOL, 4_000), (USDC, 1_000_000)])), &["spam1", "spam2"]),
+                victim("usdc", Some(user_loss(&[(USDC, 3_000_000)])), &["spam2"]),
+                victim("unknown", None, &[]),
+            ],
+            pools: Vec::new(),
+            account_intersection: Vec::new(),
+        };
+
+        let record = SandwichRecord::from_sandwich(7, &sandwich);
+        assert_eq!(record.victims, vec!["sol", "usdc", "unknown"]);
+        assert_eq!(record.victim_loss_lamports, 4_000);
+        // 多笔受害交易共享的失败尝试只计一次
+        assert_eq!(record.failed_attempts, 2);
+    }
+
+    #[test]
+    fn only_skipped_slot_errors_count_as_skipped() {
+        let rpc_error = |code| ClientError::Rpc { code, message: String::new() };
+        assert!(matches!(SlotOutcome::from_result(Err(rpc_error(-32007))), SlotOutcome::Skipped));
+        assert!(matches!(SlotOutcome::from_result(Err(rpc_error(-32009))), SlotOutcome::Skipped));
+        assert!(matches!(SlotOutcome::from_result(Err(rpc_error(-32004))), SlotOutcome::Failed(_)));
+        assert!(matches!(SlotOutcome::from_result(Err(ClientError::NotFound)), SlotOutcome::Failed(_)));
+        assert!(matches!(SlotOutcome::from_result(Ok(Vec::new())), SlotOutcome::Scanned(_)));
+    }
+}
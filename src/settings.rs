@@ -1,8 +1,11 @@
 use crate::locale::Language;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
+    // 单个RPC节点；配置了`rpc.endpoints`时忽略
+    #[serde(default)]
     pub rpc_url: String,
     pub log_level: String,
     #[serde(default)]
@@ -11,6 +14,166 @@ pub struct Settings {
     pub auto_detect_hashes: Vec<String>,
     #[serde(default)]
     pub mev_detection: MevDetectionConfig,
+    #[serde(default)]
+    pub rpc: RpcConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuditConfig {
+    // 钱包/池子审计时最多检查的交易签名数（从最新的交易开始，也可通过命令行参数--limit指定）
+    #[serde(default = "default_max_signatures")]
+    pub max_signatures: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScanConfig {
+    // slot范围扫描时同时扫描的区块数
+    #[serde(default = "default_scan_concurrency")]
+    pub concurrency: usize,
+
+    // 范围扫描的检查点文件，中断后再次运行同一范围时从中继续（也可通过命令行参数--checkpoint指定）
+    #[serde(default = "default_checkpoint_file")]
+    pub checkpoint_file: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheConfig {
+    // 交易和区块内存缓存的容量上限 (MB)，0表示不缓存
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: usize,
+
+    // 磁盘缓存目录（gzip压缩的JSON，按slot/签名保存），不设置则不使用磁盘缓存
+    #[serde(default)]
+    pub disk_dir: Option<String>,
+
+    // 磁盘缓存的容量上限 (MB)，超出时删除最旧的文件
+    #[serde(default = "default_disk_limit_mb")]
+    pub disk_limit_mb: u64,
+
+    // 离线模式：只从磁盘缓存读取，未命中时直接报错（也可通过命令行参数--offline开启）
+    #[serde(default)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcConfig {
+    // 单次请求超时 (秒)
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    // 限流、超时、5xx等暂时性错误的最大重试次数
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    // 指数退避的初始等待时间和上限 (毫秒)，实际等待时间带随机抖动
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    // 每个RPC方法的客户端限流 (每秒请求数)，0表示不限流
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: f64,
+
+    // 按方法覆盖限流，例如 getBlock = 2.0
+    #[serde(default)]
+    pub method_rate_limits: HashMap<String, f64>,
+
+    // 批量JSON-RPC每批最多包含的请求数，1表示不使用批量请求
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+
+    // 端点出错后被标记为不健康的时间 (秒)，期间只在其他端点都失败时才使用
+    #[serde(default = "default_unhealthy_cooldown_secs")]
+    pub unhealthy_cooldown_secs: u64,
+
+    // 多个RPC端点，按优先级使用并自动故障转移
+    #[serde(default)]
+    pub endpoints: Vec<RpcEndpointConfig>,
+
+    // 所有RPC查询使用的承诺级别: processed / confirmed / finalized
+    #[serde(default)]
+    pub commitment: Commitment,
+
+    // 获取区块时丢弃投票/质押交易，附近交易窗口只计算用户交易
+    #[serde(default)]
+    pub skip_vote_transactions: bool,
+}
+
+/// RPC查询的承诺级别
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+
+    /// `getTransaction`和`getBlock`不支持processed，最低使用confirmed
+    pub fn for_ledger_query(&self) -> Commitment {
+        match self {
+            Commitment::Processed => Commitment::Confirmed,
+            other => *other,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcEndpointConfig {
+    pub url: String,
+
+    // 日志中显示的名称，默认使用URL的主机名（避免输出API key）
+    #[serde(default)]
+    pub name: Option<String>,
+
+    // 优先级，数字越小越优先
+    #[serde(default)]
+    pub priority: u32,
+
+    // 附加的HTTP请求头，例如鉴权头
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    // 只用于这些RPC方法，为空表示不限制
+    #[serde(default)]
+    pub methods: Vec<String>,
+
+    // 不用于这些RPC方法，例如不提供旧区块getBlock的节点
+    #[serde(default)]
+    pub disabled_methods: Vec<String>,
+}
+
+impl Settings {
+    /// 实际使用的RPC端点：优先使用`rpc.endpoints`，否则使用`rpc_url`
+    pub fn rpc_endpoints(&self) -> Vec<RpcEndpointConfig> {
+        if !self.rpc.endpoints.is_empty() {
+            return self.rpc.endpoints.clone();
+        }
+        vec![RpcEndpointConfig {
+            url: self.rpc_url.clone(),
+            name: None,
+            priority: 0,
+            headers: HashMap::new(),
+            methods: Vec::new(),
+            disabled_methods: Vec::new(),
+        }]
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -26,6 +189,18 @@ pub struct MevDetectionConfig {
     // 忽略Jito功能 - 开启后不查询Jito API，不检查Jito小费，直接基于账户重合分析MEV
     #[serde(default = "default_ignore_jito")]
     pub ignore_jito: bool,
+
+    // 搜索窗口：目标交易前后各取的交易数 (0表示不限制)，三明治检测和束包定位共用
+    #[serde(default = "default_window_transactions")]
+    pub window_transactions: usize,
+
+    // 搜索窗口：向前后扩展的slot数，只包含与目标slot同一leader的连续slot (0表示只看目标slot)
+    #[serde(default)]
+    pub window_slots: u64,
+
+    // 严格模式：三明治涉及的所有交易都已finalized才报告，避免引用被丢弃分叉上的交易
+    #[serde(default)]
+    pub finalized_only: bool,
 }
 
 // 默认值函数
@@ -41,12 +216,113 @@ fn default_ignore_jito() -> bool {
     false
 }
 
+fn default_window_transactions() -> usize {
+    4
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    10_000
+}
+
+fn default_requests_per_second() -> f64 {
+    10.0
+}
+
+fn default_batch_size() -> usize {
+    20
+}
+
+fn default_unhealthy_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_scan_concurrency() -> usize {
+    4
+}
+
+fn default_checkpoint_file() -> String {
+    "scan-checkpoint.json".to_string()
+}
+
+fn default_max_signatures() -> usize {
+    1000
+}
+
+fn default_memory_limit_mb() -> usize {
+    256
+}
+
+fn default_disk_limit_mb() -> u64 {
+    2048
+}
+
 impl Default for MevDetectionConfig {
     fn default() -> Self {
         Self {
             similarity_threshold: default_similarity_threshold(),
             small_transfer_threshold: default_small_transfer_threshold(),
             ignore_jito: default_ignore_jito(),
+            window_transactions: default_window_transactions(),
+            window_slots: 0,
+            finalized_only: false,
+        }
+    }
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_timeout_secs(),
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            requests_per_second: default_requests_per_second(),
+            method_rate_limits: HashMap::new(),
+            batch_size: default_batch_size(),
+            unhealthy_cooldown_secs: default_unhealthy_cooldown_secs(),
+            endpoints: Vec::new(),
+            commitment: Commitment::default(),
+            skip_vote_transactions: false,
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            memory_limit_mb: default_memory_limit_mb(),
+            disk_dir: None,
+            disk_limit_mb: default_disk_limit_mb(),
+            offline: false,
+        }
+    }
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: default_scan_concurrency(),
+            checkpoint_file: default_checkpoint_file(),
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            max_signatures: default_max_signatures(),
         }
     }
 }